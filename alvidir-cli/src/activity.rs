@@ -0,0 +1,134 @@
+use std::{collections::BTreeMap, fs, io::Write, path::PathBuf};
+
+use anyhow::Result;
+use clap::Args;
+use serde::Deserialize;
+
+use crate::{
+    logging::{AUDIT_TARGET, LOGS_DIR, LOG_FILE_PREFIX},
+    pager,
+};
+
+/// The characters a sparkline is made of, from the lowest to the highest value.
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A line from the structured log files.
+#[derive(Deserialize)]
+struct LogRecord {
+    timestamp: String,
+    target: String,
+    #[serde(default)]
+    fields: AuditFields,
+}
+
+/// The fields of an audit record.
+#[derive(Default, Deserialize)]
+struct AuditFields {
+    document_id: Option<String>,
+}
+
+/// Summarize the local activity from the logs.
+#[derive(Args)]
+pub struct ActivityCommand {
+    /// The amount of most edited documents to show.
+    #[arg(long, default_value_t = 10)]
+    top: usize,
+}
+
+pub struct ActivityCli {
+    /// The directory whose activity is being summarized.
+    pub context: PathBuf,
+}
+
+impl ActivityCli {
+    pub fn execute(&self, command: ActivityCommand) -> Result<()> {
+        let mut edits_per_day = BTreeMap::<String, usize>::new();
+        let mut edits_per_document = BTreeMap::<String, usize>::new();
+
+        for record in self.audit_records()? {
+            let Some(document_id) = record.fields.document_id else {
+                continue;
+            };
+
+            // Timestamps are formatted as RFC 3339, starting with the date.
+            let day = record.timestamp.chars().take(10).collect();
+            *edits_per_day.entry(day).or_default() += 1;
+            *edits_per_document.entry(document_id).or_default() += 1;
+        }
+
+        let mut stdout = pager::stdout();
+        if edits_per_day.is_empty() {
+            writeln!(stdout, "no activity recorded")?;
+            return Ok(());
+        }
+
+        writeln!(
+            stdout,
+            "edits per day: {}",
+            sparkline(edits_per_day.values().copied())
+        )?;
+
+        for (day, edits) in &edits_per_day {
+            writeln!(stdout, "  {day}  {edits}")?;
+        }
+
+        let mut most_edited: Vec<_> = edits_per_document.into_iter().collect();
+        most_edited.sort_by(|(a_id, a_edits), (b_id, b_edits)| {
+            b_edits.cmp(a_edits).then_with(|| a_id.cmp(b_id))
+        });
+
+        let width = most_edited
+            .iter()
+            .take(command.top)
+            .map(|(document_id, _)| document_id.len())
+            .max()
+            .unwrap_or_default();
+
+        writeln!(stdout, "most edited documents:")?;
+        for (document_id, edits) in most_edited.into_iter().take(command.top) {
+            writeln!(stdout, "  {document_id:width$}  {edits}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns all the audit records in the log files of the context.
+    fn audit_records(&self) -> Result<Vec<LogRecord>> {
+        let logs_dir = self.context.join(LOGS_DIR);
+        if !logs_dir.exists() {
+            return Ok(Vec::default());
+        }
+
+        let mut records = Vec::default();
+        for entry in fs::read_dir(logs_dir)? {
+            let entry = entry?;
+            if !entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(LOG_FILE_PREFIX)
+            {
+                continue;
+            }
+
+            for line in fs::read_to_string(entry.path())?.lines() {
+                match serde_json::from_str::<LogRecord>(line) {
+                    Ok(record) if record.target == AUDIT_TARGET => records.push(record),
+                    Ok(_) => {}
+                    Err(err) => {
+                        tracing::debug!(error = err.to_string(), line, "parsing log record")
+                    }
+                }
+            }
+        }
+
+        Ok(records)
+    }
+}
+
+/// Renders the given values as a sparkline.
+fn sparkline(values: impl Iterator<Item = usize> + Clone) -> String {
+    let max = values.clone().max().unwrap_or_default().max(1);
+    values
+        .map(|value| SPARKS[(value * (SPARKS.len() - 1)).div_ceil(max)])
+        .collect()
+}
@@ -0,0 +1,294 @@
+use std::{collections::BTreeMap, fs, path::PathBuf, sync::Arc};
+
+use alvidir::{
+    document::{lazy::LazyDocument, DocumentRepository, DocumentStore, DocumentTrash},
+    graph::Source,
+    schema::{
+        ops::{delete::Delete, save::Save},
+        transaction::{Context, Transaction},
+        Error, Schema,
+    },
+};
+use anyhow::{Context as _, Result};
+use clap::Args;
+use serde::Deserialize;
+
+use crate::{document::Document, error::CliError, logging};
+
+/// A change to apply on the documents, as written in a batch file.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+enum Operation {
+    /// Creates or overwrites the document with the given id.
+    Save {
+        id: PathBuf,
+        #[serde(default)]
+        content: String,
+    },
+    /// Moves the document with the given id into the trash.
+    Delete { id: PathBuf },
+}
+
+/// A write on disk performed by an [`Operation`], as required to revert it.
+enum Written {
+    /// The document was saved, overwriting the previous version, if any.
+    Save {
+        id: PathBuf,
+        previous: Option<Document>,
+    },
+    /// The document was moved into the trash.
+    Trash { id: PathBuf },
+}
+
+/// Apply all the operations in a YAML or JSON file as a whole, or none of them if any fails.
+///
+/// Documents are written only once every operation passed the constraints. If writing any of them
+/// fails, the ones written so far are reverted, moving those that did not exist into the trash.
+#[derive(Args)]
+pub struct ApplyCommand {
+    /// The file listing the operations, each of them with an 'op' (save or delete) and an 'id'.
+    file: PathBuf,
+}
+
+pub struct ApplyCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub document_repo: Arc<DocumentRepo>,
+}
+
+impl<DocumentRepo> ApplyCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document> + DocumentStore + DocumentTrash,
+{
+    pub fn execute(&self, command: ApplyCommand) -> Result<()> {
+        let content = fs::read_to_string(&command.file)
+            .with_context(|| format!("reading {}", command.file.display()))?;
+
+        let operations: Vec<Operation> =
+            if command.file.extension().is_some_and(|ext| ext == "json") {
+                serde_json::from_str(&content)?
+            } else {
+                serde_yaml::from_str(&content)?
+            };
+
+        self.validate(&operations)?;
+        let mut on_disk = self.originals(&operations);
+
+        // Every operation runs in the same transaction, so none is committed if any fails, and
+        // documents are written within it, so it is not committed if any write fails either.
+        let mut current = 0;
+        let mut written = Vec::new();
+        let result = self.schema.transaction().with(|ctx: Context<'_, _>| {
+            for (index, operation) in operations.iter().enumerate() {
+                current = index;
+                self.stage(&ctx, operation)?;
+            }
+
+            for (index, operation) in operations.iter().enumerate() {
+                current = index;
+                written.push(self.write(operation, &mut on_disk)?);
+            }
+
+            Ok(())
+        });
+
+        if result.is_err() {
+            self.rollback(written);
+        }
+
+        result.with_context(|| format!("operation {}", current + 1))
+    }
+
+    /// Fails if any of the given operations cannot be performed regardless of the constraints in
+    /// the schema, like deleting a document that does not exist.
+    fn validate(&self, operations: &[Operation]) -> Result<()> {
+        let graph = self.schema.read();
+        let mut exists: BTreeMap<&PathBuf, bool> = BTreeMap::new();
+        for (index, operation) in operations.iter().enumerate() {
+            let result = match operation {
+                Operation::Save { id, .. } if id.as_os_str().is_empty() => {
+                    Err(anyhow::Error::msg("document id must be set"))
+                }
+                Operation::Save { id, .. } => {
+                    exists.insert(id, true);
+                    Ok(())
+                }
+                Operation::Delete { id } => {
+                    if *exists.entry(id).or_insert_with(|| graph.contains(id)) {
+                        exists.insert(id, false);
+                        Ok(())
+                    } else {
+                        Err(CliError::DocumentNotFound(id.clone()).into())
+                    }
+                }
+            };
+
+            result.with_context(|| format!("operation {}", index + 1))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the version of every document the given operations write, if it exists.
+    fn originals(&self, operations: &[Operation]) -> BTreeMap<PathBuf, Option<Document>> {
+        let graph = self.schema.read();
+        operations
+            .iter()
+            .map(|operation| match operation {
+                Operation::Save { id, .. } | Operation::Delete { id } => id,
+            })
+            .map(|id| {
+                let document = graph
+                    .contains(id)
+                    .then(|| self.document_repo.find_by_id(id))
+                    .flatten();
+
+                (id.clone(), document)
+            })
+            .collect()
+    }
+
+    /// Writes the given operation on disk, given the version of the documents on it, returning
+    /// how to revert it.
+    fn write(
+        &self,
+        operation: &Operation,
+        on_disk: &mut BTreeMap<PathBuf, Option<Document>>,
+    ) -> Result<Written, Error> {
+        match operation {
+            Operation::Save { id, content } => {
+                let document = Document {
+                    path: id.clone(),
+                    bytes: content.clone().into_bytes(),
+                };
+
+                self.document_repo.save(&document)?;
+                logging::audit("save", id.display());
+
+                let previous = on_disk.insert(id.clone(), Some(document)).flatten();
+                Ok(Written::Save {
+                    id: id.clone(),
+                    previous,
+                })
+            }
+            Operation::Delete { id } => {
+                self.document_repo.trash(id)?;
+                logging::audit("delete", id.display());
+
+                on_disk.insert(id.clone(), None);
+                Ok(Written::Trash { id: id.clone() })
+            }
+        }
+    }
+
+    /// Reverts the given writes, from the newest to the oldest.
+    ///
+    /// Reverting is best effort: a write that cannot be reverted is logged and skipped.
+    fn rollback(&self, written: Vec<Written>) {
+        for change in written.into_iter().rev() {
+            let (action, id, result) = match change {
+                Written::Save { id, previous: None } => {
+                    let result = self.document_repo.trash(&id);
+                    ("delete", id, result)
+                }
+                Written::Save {
+                    id,
+                    previous: Some(document),
+                } => {
+                    let result = self.document_repo.save(&document);
+                    ("save", id, result)
+                }
+                Written::Trash { id } => {
+                    let result = self.document_repo.restore(&id);
+                    ("restore", id, result)
+                }
+            };
+
+            match result {
+                Ok(_) => logging::audit(action, id.display()),
+                Err(err) => {
+                    tracing::error!(error = err.to_string(), id = ?id, action, "rolling back write")
+                }
+            }
+        }
+    }
+
+    /// Performs the given operation in the given transaction.
+    fn stage(
+        &self,
+        ctx: &Context<'_, LazyDocument<DocumentRepo>>,
+        operation: &Operation,
+    ) -> Result<(), Error> {
+        match operation {
+            Operation::Save { id, content } => {
+                let document = Document {
+                    path: id.clone(),
+                    bytes: content.clone().into_bytes(),
+                };
+
+                Save::new(LazyDocument::new(self.document_repo.clone(), document))
+                    .execute(ctx.transaction())?;
+            }
+            Operation::Delete { id } => {
+                Delete::new(id.clone()).execute(ctx.transaction())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use alvidir::graph::Source;
+
+    use crate::repository::fixtures::local_schema;
+
+    use super::{ApplyCli, ApplyCommand};
+
+    #[test]
+    fn failing_write_leaves_nothing_applied() {
+        let context = tempfile::tempdir().unwrap();
+        fs::write(context.path().join("a.md"), "a").unwrap();
+        fs::write(context.path().join("b.md"), "b").unwrap();
+        // A file where a directory is required, so saving any document under it fails.
+        fs::write(context.path().join("x"), "").unwrap();
+
+        let file = context.path().join("operations.yaml");
+        fs::write(
+            &file,
+            "- {op: save, id: new, content: new}\n\
+             - {op: save, id: a, content: changed}\n\
+             - {op: delete, id: b}\n\
+             - {op: save, id: x/y}\n",
+        )
+        .unwrap();
+
+        let (document_repo, schema) = local_schema(context.path());
+        let cli = ApplyCli {
+            schema: schema.clone(),
+            document_repo,
+        };
+
+        let err = cli.execute(ApplyCommand { file }).unwrap_err();
+        assert_eq!(err.to_string(), "operation 4");
+
+        assert!(!context.path().join("new.md").exists());
+        assert_eq!(
+            fs::read_to_string(context.path().join("a.md")).unwrap(),
+            "a"
+        );
+        assert_eq!(
+            fs::read_to_string(context.path().join("b.md")).unwrap(),
+            "b"
+        );
+
+        let graph = schema.read();
+        assert!(!graph.contains(&PathBuf::from("new")));
+        assert!(graph.contains(&PathBuf::from("b")));
+    }
+}
@@ -0,0 +1,413 @@
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    fmt::Debug,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use alvidir::{
+    clock::Clock,
+    deref::TryDeref,
+    document::{lazy::LazyDocument, DocumentRepository, DocumentStore, DocumentTrash},
+    id::Identify,
+    schema::{
+        ops::{delete::Delete, save::Save},
+        transaction::Transaction,
+        Schema,
+    },
+};
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use serde::Serialize;
+
+use crate::{
+    document::Document, dryrun::WriteMode, error::CliError, output::OutputFormat,
+    repository::LocalDocumentRepository,
+};
+
+/// The directory, relative to the context, in which backups are kept.
+pub const BACKUPS_DIR: &str = ".alvidir/backups";
+
+/// A backup as printed by the listing command.
+#[derive(Serialize)]
+struct BackupRecord {
+    /// The id of the backup, as the milliseconds since the Unix epoch it was created at.
+    id: u64,
+    /// The moment the backup was created at, in RFC 3339 format.
+    created_at: String,
+    /// The amount of documents in the backup.
+    documents: usize,
+}
+
+#[derive(Args)]
+struct BackupCreateArgs {
+    /// The amount of backups to keep, removing the oldest ones.
+    #[arg(
+        long,
+        default_value_t = 5,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+    )]
+    keep: usize,
+}
+
+#[derive(Args)]
+struct BackupRestoreArgs {
+    /// The id of the backup to restore.
+    id: u64,
+}
+
+#[derive(Subcommand)]
+enum BackupSubCommand {
+    /// Create a backup of all documents.
+    Create(BackupCreateArgs),
+    /// List all backups.
+    #[command(alias("ls"))]
+    List,
+    /// Restore all documents from a backup.
+    ///
+    /// The documents are validated as a whole before any of them is written, but writing them is
+    /// not atomic: if it fails midway, the documents written so far are kept, and the backup may be
+    /// restored again.
+    Restore(BackupRestoreArgs),
+}
+
+/// Manage backups of the documents.
+#[derive(Args)]
+pub struct BackupCommand {
+    /// The action to perform.
+    #[command(subcommand)]
+    subcommand: BackupSubCommand,
+}
+
+pub struct BackupCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+    <DocumentRepo::Document as Identify>::Id: Clone,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub document_repo: Arc<DocumentRepo>,
+    /// The directory in which backups are kept.
+    pub backups_dir: PathBuf,
+    /// The file's extension.
+    pub extension: String,
+    /// The format to print results in.
+    pub output: OutputFormat,
+    /// The clock telling the moment backups are created at.
+    pub clock: Arc<dyn Clock>,
+    /// Whether backups may be created or removed.
+    pub mode: WriteMode,
+}
+
+impl<DocumentRepo> BackupCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document> + DocumentStore + DocumentTrash,
+    DocumentRepo::Document: Debug + Clone,
+    <DocumentRepo::Document as Identify>::Id: Ord + Clone + FromStr + Debug,
+    <<DocumentRepo::Document as Identify>::Id as FromStr>::Err: 'static + Error + Sync + Send,
+{
+    pub fn execute(&self, command: BackupCommand) -> Result<()> {
+        match command.subcommand {
+            BackupSubCommand::Create(args) => {
+                let backup_id = self.create()?;
+                self.rotate(args.keep)?;
+
+                writeln!(io::stdout(), "{backup_id}")?;
+            }
+            BackupSubCommand::List => {
+                let records = self.backups()?.into_iter().map(|id| {
+                    let created_at = UNIX_EPOCH + Duration::from_millis(id);
+                    BackupRecord {
+                        id,
+                        created_at: humantime::format_rfc3339_millis(created_at).to_string(),
+                        documents: Arc::new(self.backup_repo(id)).all().count(),
+                    }
+                });
+
+                self.output.print_all(records, |stdout, record| {
+                    writeln!(
+                        stdout,
+                        "{}  {}  {} documents",
+                        record.id, record.created_at, record.documents
+                    )?;
+
+                    Ok(())
+                })?;
+            }
+            BackupSubCommand::Restore(args) => self.restore(args.id)?,
+        }
+
+        Ok(())
+    }
+
+    /// Copies all the documents into a new backup, returning its id.
+    fn create(&self) -> Result<u64> {
+        // Milliseconds, so that backups created within the same second do not collide.
+        let backup_id = self.clock.now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+        let backup_repo = self.backup_repo(backup_id);
+        if backup_repo.context.exists() {
+            return Err(CliError::BackupExists(backup_id).into());
+        }
+
+        if !self
+            .mode
+            .allows(format_args!("create backup {backup_id}"))?
+        {
+            return Ok(backup_id);
+        }
+
+        fs::create_dir_all(&backup_repo.context)?;
+        for document in self.schema.read().into_iter() {
+            let Some(document) = document.try_deref() else {
+                continue;
+            };
+
+            backup_repo.save(document)?;
+        }
+
+        Ok(backup_id)
+    }
+
+    /// Replaces all the documents by the ones in the given backup.
+    ///
+    /// Documents not present in the backup are moved into the trash instead of being removed. The
+    /// backup is validated as a whole, but written document by document, as told in the help of
+    /// [`BackupSubCommand::Restore`].
+    fn restore(&self, backup_id: u64) -> Result<()> {
+        let backup_repo = Arc::new(self.backup_repo(backup_id));
+        if !backup_repo.context.exists() {
+            return Err(CliError::BackupNotFound(backup_id).into());
+        }
+
+        let documents: Vec<Document> = backup_repo
+            .all()
+            .filter_map(|document| document.try_deref().cloned())
+            .collect();
+
+        let backup_ids: BTreeSet<_> = documents.iter().map(|document| document.id()).collect();
+        let deleted: Vec<_> = self
+            .schema
+            .read()
+            .into_iter()
+            .map(|document| document.id().clone())
+            .filter(|document_id| !backup_ids.contains(document_id))
+            .collect();
+
+        self.schema.transaction().with(|ctx| {
+            for document_id in &deleted {
+                Delete::new(document_id.clone()).execute(ctx.transaction())?;
+            }
+
+            for document in &documents {
+                Save::new(LazyDocument::new(
+                    self.document_repo.clone(),
+                    document.clone(),
+                ))
+                .execute(ctx.transaction())?;
+            }
+
+            Ok(())
+        })?;
+
+        for document_id in &deleted {
+            self.document_repo.trash(document_id)?;
+        }
+
+        for document in &documents {
+            self.document_repo.save(document)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the oldest backups until there are, at most, the given amount of them.
+    fn rotate(&self, keep: usize) -> Result<()> {
+        let backups = self.backups()?;
+        for backup_id in backups.iter().take(backups.len().saturating_sub(keep)) {
+            if self
+                .mode
+                .allows(format_args!("remove backup {backup_id}"))?
+            {
+                fs::remove_dir_all(self.backup_repo(*backup_id).context)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the id of all the backups, from the oldest to the newest.
+    fn backups(&self) -> Result<Vec<u64>> {
+        if !self.backups_dir.exists() {
+            return Ok(Vec::default());
+        }
+
+        let mut backups = Vec::default();
+        for entry in fs::read_dir(&self.backups_dir)? {
+            if let Ok(backup_id) = entry?.file_name().to_string_lossy().parse() {
+                backups.push(backup_id);
+            }
+        }
+
+        backups.sort();
+        Ok(backups)
+    }
+
+    /// Returns the repository of the backup with the given id.
+    fn backup_repo(&self, backup_id: u64) -> LocalDocumentRepository {
+        LocalDocumentRepository {
+            context: self.backups_dir.join(backup_id.to_string()),
+            extension: self.extension.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::Path,
+        sync::Arc,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use alvidir::clock::fixtures::MockClock;
+    use clap::Parser;
+
+    use crate::{
+        dryrun::WriteMode,
+        error::ErrorKind,
+        output::OutputFormat,
+        repository::{fixtures::local_schema, LocalDocumentRepository},
+    };
+
+    use super::{BackupCli, BackupCreateArgs, BACKUPS_DIR};
+
+    fn backup_cli(
+        context: &Path,
+        clock: Arc<MockClock>,
+        mode: WriteMode,
+    ) -> BackupCli<LocalDocumentRepository> {
+        let (document_repo, schema) = local_schema(context);
+
+        BackupCli {
+            schema,
+            document_repo,
+            backups_dir: context.join(BACKUPS_DIR),
+            extension: "md".to_string(),
+            output: OutputFormat::default(),
+            clock,
+            mode,
+        }
+    }
+
+    #[test]
+    fn create_and_rotate_respect_write_mode() {
+        struct Test {
+            name: &'static str,
+            mode: WriteMode,
+            read_only: bool,
+            backups: Vec<u64>,
+        }
+
+        vec![
+            Test {
+                name: "write",
+                mode: WriteMode::Write,
+                read_only: false,
+                backups: vec![100_000],
+            },
+            Test {
+                name: "read only",
+                mode: WriteMode::ReadOnly,
+                read_only: true,
+                backups: vec![1, 2],
+            },
+            Test {
+                name: "dry run",
+                mode: WriteMode::DryRun,
+                read_only: false,
+                backups: vec![1, 2],
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let context = tempfile::tempdir().unwrap();
+            fs::write(context.path().join("a.md"), "content").unwrap();
+            for backup_id in [1, 2] {
+                fs::create_dir_all(context.path().join(BACKUPS_DIR).join(backup_id.to_string()))
+                    .unwrap();
+            }
+
+            let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(100)));
+            let cli = backup_cli(context.path(), clock, test.mode);
+            for result in [cli.create().map(|_| ()), cli.rotate(1)] {
+                match result {
+                    Ok(_) => assert!(!test.read_only, "{}: write must fail", test.name),
+                    Err(err) => assert!(
+                        test.read_only && ErrorKind::of(&err) == ErrorKind::ReadOnly,
+                        "{}: unexpected error {err}",
+                        test.name
+                    ),
+                }
+            }
+
+            assert_eq!(cli.backups().unwrap(), test.backups, "{}", test.name);
+        });
+    }
+
+    #[test]
+    fn backups_within_the_same_second_do_not_collide() {
+        let context = tempfile::tempdir().unwrap();
+        fs::write(context.path().join("a.md"), "content").unwrap();
+
+        let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(100)));
+        let cli = backup_cli(context.path(), clock.clone(), WriteMode::Write);
+
+        let first = cli.create().unwrap();
+        clock.advance(Duration::from_millis(500));
+        let second = cli.create().unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(cli.backups().unwrap(), vec![first, second]);
+    }
+
+    #[test]
+    fn keep_must_be_positive() {
+        #[derive(Parser)]
+        struct Cli {
+            #[command(flatten)]
+            args: BackupCreateArgs,
+        }
+
+        struct Test {
+            name: &'static str,
+            keep: &'static str,
+            want: Option<usize>,
+        }
+
+        vec![
+            Test {
+                name: "zero",
+                keep: "0",
+                want: None,
+            },
+            Test {
+                name: "one",
+                keep: "1",
+                want: Some(1),
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let got = Cli::try_parse_from(["backup", "--keep", test.keep])
+                .ok()
+                .map(|cli| cli.args.keep);
+
+            assert_eq!(got, test.want, "{}", test.name);
+        });
+    }
+}
@@ -0,0 +1,87 @@
+use std::{path::PathBuf, sync::Arc};
+
+use alvidir::{
+    document::{lazy::LazyDocument, DocumentArchive, DocumentRepository},
+    schema::Schema,
+};
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    color::{paint, Role},
+    constraints::Integrity,
+    document::{Document, DocumentLink},
+    error::CliError,
+    output::OutputFormat,
+};
+
+/// A link to a missing document as printed by the check command.
+#[derive(Serialize)]
+struct DanglingRecord {
+    /// The id of the document the link is in.
+    from: PathBuf,
+    /// The id of the missing document.
+    to: PathBuf,
+}
+
+/// Check that every link between documents points to an existing one, except for links in
+/// archived documents, unless configured otherwise in the constraints of the context.
+#[derive(Args)]
+pub struct CheckCommand;
+
+pub struct CheckCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub document_repo: Arc<DocumentRepo>,
+    /// The links to report.
+    pub integrity: Integrity,
+    /// The format to print results in.
+    pub output: OutputFormat,
+}
+
+impl<DocumentRepo> CheckCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document> + DocumentArchive,
+{
+    pub fn execute(&self, _: CheckCommand) -> Result<()> {
+        if !self.integrity.enabled {
+            return Ok(());
+        }
+
+        let mut dangling = self.schema.read().dangling::<DocumentLink>();
+        if !self.integrity.include_archived {
+            let archived = self.document_repo.archived();
+            dangling.retain(|edge| !archived.contains(&edge.from));
+        }
+
+        let count = dangling.len();
+        let records = dangling.into_iter().map(|edge| DanglingRecord {
+            from: edge.from,
+            to: edge.to,
+        });
+
+        self.output.print_all(records, |stdout, record| {
+            writeln!(
+                stdout,
+                "{} -> {}",
+                paint(Role::Id, format_args!("{:?}", record.from)),
+                paint(Role::Error, format_args!("{:?}", record.to))
+            )?;
+
+            Ok(())
+        })?;
+
+        if count > 0 {
+            return Err(CliError::Violations {
+                count,
+                what: "dangling links",
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use alvidir::{
+    deref::TryDeref,
+    document::{
+        lazy::LazyDocument, DocumentArchive, DocumentRepository, DocumentStore, DocumentTrash,
+    },
+    id::Identify,
+    schema::{Error, Result as SchemaResult, Schema},
+};
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    color::{paint, Role},
+    document::Document,
+    dryrun::WriteMode,
+    error::{schema_error, CliError},
+    output::OutputFormat,
+};
+
+/// The file, relative to the context, in which the checksum of every document is kept.
+pub const CHECKSUMS_FILE: &str = ".alvidir/checksums.json";
+
+/// Returns the hex-encoded SHA-256 hash of the given content.
+pub fn checksum(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// The checksum of every document, by id.
+#[derive(Debug, Default)]
+pub struct Checksums {
+    /// The file in which checksums are kept as a JSON object.
+    path: PathBuf,
+    checksums: BTreeMap<PathBuf, String>,
+}
+
+impl Checksums {
+    /// Reads the checksums in the given file, if it exists, or returns an empty set otherwise.
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let checksums = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)
+                .map_err(|err| anyhow::Error::msg(format!("parsing {}: {err}", path.display())))?
+        } else {
+            BTreeMap::default()
+        };
+
+        Ok(Self { path, checksums })
+    }
+
+    /// Returns the checksum of the document with the given id, if any.
+    pub fn get(&self, id: &Path) -> Option<&str> {
+        self.checksums.get(id).map(String::as_str)
+    }
+
+    /// Sets the checksum of the given document.
+    pub fn insert(&mut self, document: &Document) {
+        self.checksums
+            .insert(document.path.clone(), checksum(&document.bytes));
+    }
+
+    /// Forgets the checksum of the document with the given id.
+    pub fn remove(&mut self, id: &Path) {
+        self.checksums.remove(id);
+    }
+
+    /// Returns the ids of all the documents with a checksum.
+    pub fn ids(&self) -> impl Iterator<Item = &Path> {
+        self.checksums.keys().map(PathBuf::as_path)
+    }
+
+    /// Overwrites the file with the current checksums.
+    pub fn store(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = fs::File::create(&self.path)?;
+        serde_json::to_writer_pretty(&mut file, &self.checksums)?;
+        writeln!(file)?;
+        Ok(())
+    }
+}
+
+/// A [`DocumentRepository`] decorator that keeps the [`Checksums`] of documents up to date.
+pub struct ChecksummedRepository<DocumentRepo> {
+    /// The repository being decorated.
+    document_repo: DocumentRepo,
+    checksums: Arc<Mutex<Checksums>>,
+}
+
+impl<DocumentRepo> ChecksummedRepository<DocumentRepo> {
+    pub fn new(document_repo: DocumentRepo, checksums: Checksums) -> Self {
+        Self {
+            document_repo,
+            checksums: Arc::new(Mutex::new(checksums)),
+        }
+    }
+
+    /// Applies the given change into the checksums and stores them.
+    fn update(&self, f: impl FnOnce(&mut Checksums)) -> SchemaResult<()> {
+        let mut checksums = self
+            .checksums
+            .lock()
+            .map_err(|err| Error::custom(err.to_string()))?;
+
+        f(&mut checksums);
+        checksums.store().map_err(schema_error)
+    }
+}
+
+impl<DocumentRepo> DocumentRepository for ChecksummedRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    type Document = Document;
+
+    fn find_by_id(&self, id: &<Self::Document as Identify>::Id) -> Option<Self::Document> {
+        self.document_repo.find_by_id(id)
+    }
+}
+
+impl<DocumentRepo> DocumentStore for ChecksummedRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentStore<Document = Document>,
+{
+    fn save(&self, document: &Self::Document) -> SchemaResult<()> {
+        self.document_repo.save(document)?;
+        self.update(|checksums| checksums.insert(document))
+    }
+}
+
+impl<DocumentRepo> DocumentTrash for ChecksummedRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentTrash<Document = Document>,
+{
+    fn trash(&self, id: &<Self::Document as Identify>::Id) -> SchemaResult<()> {
+        self.document_repo.trash(id)?;
+        self.update(|checksums| checksums.remove(id))
+    }
+
+    fn restore(&self, id: &<Self::Document as Identify>::Id) -> SchemaResult<()> {
+        self.document_repo.restore(id)?;
+        let document = self.document_repo.find_by_id(id);
+        self.update(|checksums| match &document {
+            Some(document) => checksums.insert(document),
+            None => checksums.remove(id),
+        })
+    }
+
+    fn purge(&self) -> SchemaResult<()> {
+        self.document_repo.purge()
+    }
+
+    fn trashed(&self) -> Vec<<Self::Document as Identify>::Id> {
+        self.document_repo.trashed()
+    }
+}
+
+impl<DocumentRepo> DocumentArchive for ChecksummedRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentArchive<Document = Document>,
+{
+    fn archive(&self, id: &<Self::Document as Identify>::Id) -> SchemaResult<()> {
+        self.document_repo.archive(id)
+    }
+
+    fn unarchive(&self, id: &<Self::Document as Identify>::Id) -> SchemaResult<()> {
+        self.document_repo.unarchive(id)
+    }
+
+    fn archived(&self) -> Vec<<Self::Document as Identify>::Id> {
+        self.document_repo.archived()
+    }
+}
+
+/// The outcome of verifying a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerifyStatus {
+    /// The content of the document does not match its checksum.
+    Modified,
+    /// The document has a checksum but does not exist.
+    Missing,
+    /// The document exists but has no checksum.
+    Untracked,
+}
+
+/// A document failing verification as printed by the verify command.
+#[derive(Serialize)]
+struct VerifyRecord {
+    /// The id of the document.
+    id: PathBuf,
+    status: VerifyStatus,
+}
+
+/// Compare the content of every document against its recorded checksum.
+#[derive(Args)]
+pub struct VerifyCommand {
+    /// Record the checksum of every document as it is now instead, accepting any change.
+    #[arg(long)]
+    update: bool,
+}
+
+pub struct VerifyCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    /// The file in which checksums are kept.
+    pub checksums_file: PathBuf,
+    /// The format to print results in.
+    pub output: OutputFormat,
+    /// Whether checksums may be updated.
+    pub mode: WriteMode,
+}
+
+impl<DocumentRepo> VerifyCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    pub fn execute(&self, command: VerifyCommand) -> Result<()> {
+        let mut checksums = Checksums::load(self.checksums_file.clone())?;
+        let documents: BTreeMap<PathBuf, Document> = self
+            .schema
+            .read()
+            .into_iter()
+            .filter_map(|document| document.try_deref().cloned())
+            .map(|document| (document.path.clone(), document))
+            .collect();
+
+        if command.update {
+            if !self.mode.allows(format_args!("update checksums"))? {
+                return Ok(());
+            }
+
+            checksums = Checksums {
+                path: self.checksums_file.clone(),
+                checksums: Default::default(),
+            };
+
+            documents
+                .values()
+                .for_each(|document| checksums.insert(document));
+
+            return checksums.store();
+        }
+
+        let mut records: Vec<VerifyRecord> = documents
+            .values()
+            .filter_map(|document| {
+                let status = match checksums.get(&document.path) {
+                    None => VerifyStatus::Untracked,
+                    Some(expected) if expected != checksum(&document.bytes) => {
+                        VerifyStatus::Modified
+                    }
+                    Some(_) => return None,
+                };
+
+                Some(VerifyRecord {
+                    id: document.path.clone(),
+                    status,
+                })
+            })
+            .chain(
+                checksums
+                    .ids()
+                    .filter(|id| !documents.contains_key(*id))
+                    .map(|id| VerifyRecord {
+                        id: id.to_path_buf(),
+                        status: VerifyStatus::Missing,
+                    }),
+            )
+            .collect();
+
+        records.sort_by(|a, b| a.id.cmp(&b.id));
+
+        // Untracked documents are reported, but they are not evidence of tampering.
+        let failed = records
+            .iter()
+            .filter(|record| record.status != VerifyStatus::Untracked)
+            .count();
+
+        self.output.print_all(records, |stdout, record| {
+            let (status, role) = match record.status {
+                VerifyStatus::Modified => ("modified", Role::Error),
+                VerifyStatus::Missing => ("missing", Role::Error),
+                VerifyStatus::Untracked => ("untracked", Role::Warning),
+            };
+
+            writeln!(
+                stdout,
+                "{}  {}",
+                paint(role, format_args!("{status:<9}")),
+                paint(Role::Id, record.id.display())
+            )?;
+            Ok(())
+        })?;
+
+        if failed > 0 {
+            return Err(CliError::Violations {
+                count: failed,
+                what: "documents failed verification",
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{
+        dryrun::WriteMode, error::ErrorKind, output::OutputFormat,
+        repository::fixtures::local_schema,
+    };
+
+    use super::{VerifyCli, VerifyCommand, CHECKSUMS_FILE};
+
+    #[test]
+    fn update_respects_write_mode() {
+        struct Test {
+            name: &'static str,
+            mode: WriteMode,
+            read_only: bool,
+            stored: bool,
+        }
+
+        vec![
+            Test {
+                name: "write",
+                mode: WriteMode::Write,
+                read_only: false,
+                stored: true,
+            },
+            Test {
+                name: "read only",
+                mode: WriteMode::ReadOnly,
+                read_only: true,
+                stored: false,
+            },
+            Test {
+                name: "dry run",
+                mode: WriteMode::DryRun,
+                read_only: false,
+                stored: false,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let context = tempfile::tempdir().unwrap();
+            fs::write(context.path().join("a.md"), "content").unwrap();
+
+            let (_, schema) = local_schema(context.path());
+
+            let cli = VerifyCli {
+                schema,
+                checksums_file: context.path().join(CHECKSUMS_FILE),
+                output: OutputFormat::default(),
+                mode: test.mode,
+            };
+
+            match cli.execute(VerifyCommand { update: true }) {
+                Ok(_) => assert!(!test.read_only, "{}: update must fail", test.name),
+                Err(err) => assert!(
+                    test.read_only && ErrorKind::of(&err) == ErrorKind::ReadOnly,
+                    "{}: unexpected error {err}",
+                    test.name
+                ),
+            }
+
+            assert_eq!(
+                context.path().join(CHECKSUMS_FILE).exists(),
+                test.stored,
+                "{}",
+                test.name
+            );
+        });
+    }
+}
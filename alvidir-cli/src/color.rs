@@ -0,0 +1,118 @@
+use std::{
+    env,
+    fmt::Display,
+    io::{self, IsTerminal},
+    sync::OnceLock,
+};
+
+use serde::Deserialize;
+
+/// The environment variable disabling colors if set to any non-empty value (see no-color.org).
+const NO_COLOR_ENV: &str = "NO_COLOR";
+
+/// The theme to paint the output with, or none if colors are disabled.
+static THEME: OnceLock<Option<Theme>> = OnceLock::new();
+
+/// The meaning of a piece of text, determining its color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// The id of a document.
+    Id,
+    /// Something wrong, like a dangling link or a violated constraint.
+    Error,
+    /// Something that requires attention.
+    Warning,
+    /// Secondary information, like scores or timestamps.
+    Muted,
+}
+
+/// A style of the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Color {
+    /// The default style of the terminal.
+    None,
+    Bold,
+    Dim,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    /// Returns the ANSI escape code of self, if any.
+    fn code(self) -> Option<&'static str> {
+        match self {
+            Color::None => None,
+            Color::Bold => Some("1"),
+            Color::Dim => Some("2"),
+            Color::Black => Some("30"),
+            Color::Red => Some("31"),
+            Color::Green => Some("32"),
+            Color::Yellow => Some("33"),
+            Color::Blue => Some("34"),
+            Color::Magenta => Some("35"),
+            Color::Cyan => Some("36"),
+            Color::White => Some("37"),
+        }
+    }
+}
+
+/// The color of each [`Role`], as configured in the `theme` table of the configuration file.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    pub id: Color,
+    pub error: Color,
+    pub warning: Color,
+    pub muted: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            id: Color::Cyan,
+            error: Color::Red,
+            warning: Color::Yellow,
+            muted: Color::Dim,
+        }
+    }
+}
+
+impl Theme {
+    /// Returns the color of the given role.
+    fn color(&self, role: Role) -> Color {
+        match role {
+            Role::Id => self.id,
+            Role::Error => self.error,
+            Role::Warning => self.warning,
+            Role::Muted => self.muted,
+        }
+    }
+}
+
+/// Enables colors with the given theme if the standard output is a terminal and colors are not
+/// disabled by the [`NO_COLOR_ENV`] environment variable.
+pub fn init(enabled: bool, theme: Theme) {
+    let disabled_by_env = env::var_os(NO_COLOR_ENV).is_some_and(|value| !value.is_empty());
+    let enabled = enabled && !disabled_by_env && io::stdout().is_terminal();
+    let _ = THEME.set(enabled.then_some(theme));
+}
+
+/// Returns the given text painted as the given role, or as is if colors are disabled.
+pub fn paint(role: Role, text: impl Display) -> String {
+    match THEME
+        .get()
+        .copied()
+        .flatten()
+        .and_then(|theme| theme.color(role).code())
+    {
+        Some(code) => format!("\x1b[{code}m{text}\x1b[0m"),
+        None => text.to_string(),
+    }
+}
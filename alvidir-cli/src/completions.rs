@@ -0,0 +1,37 @@
+use std::io;
+
+use anyhow::Result;
+use clap::Args;
+use clap_complete::Shell;
+
+/// Print the shell completion script of the command line.
+#[derive(Args)]
+pub struct CompletionsCommand {
+    /// The shell to generate the completion script for.
+    #[arg(value_enum)]
+    shell: Shell,
+}
+
+/// Print the manual page of the command line in roff format.
+#[derive(Args)]
+pub struct ManCommand;
+
+pub struct CompletionsCli {
+    /// The definition of the whole command line.
+    pub command: clap::Command,
+}
+
+impl CompletionsCli {
+    pub fn execute(&self, command: CompletionsCommand) -> Result<()> {
+        let mut cli = self.command.clone();
+        let name = cli.get_name().to_string();
+        clap_complete::generate(command.shell, &mut cli, name, &mut io::stdout());
+
+        Ok(())
+    }
+
+    pub fn man(&self, _: ManCommand) -> Result<()> {
+        clap_mangen::Man::new(self.command.clone()).render(&mut io::stdout())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use clap::{builder::OsStr, ValueEnum};
+use serde::Deserialize;
+
+use crate::{color::Theme, output::OutputFormat};
+
+/// The file, relative to the user's configuration directory, in which the CLI is configured.
+pub const CONFIG_FILE: &str = "alvidir/config.toml";
+
+/// The default value of the global options, as configured in the [`CONFIG_FILE`].
+///
+/// Any option given in the command line or the environment takes precedence over its configured
+/// value. A configured flag is turned off by its negated counterpart, e.g. `--no-offline`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The base directory.
+    pub context: Option<PathBuf>,
+    /// The file's extension.
+    pub extension: Option<String>,
+    /// The format to print results in.
+    pub output: Option<OutputFormat>,
+    /// The base url of the service validating document ids before saving them.
+    pub validation_url: Option<String>,
+    /// Save documents even if the validation service is unreachable.
+    pub offline: Option<bool>,
+    /// Do not pipe long outputs into a pager.
+    pub no_pager: Option<bool>,
+    /// Do not highlight the output with colors.
+    pub no_color: Option<bool>,
+    /// Do not report the progress of long-running commands.
+    pub quiet: Option<bool>,
+    /// The color to highlight each kind of output with.
+    pub theme: Theme,
+}
+
+impl Config {
+    /// Returns the path of the configuration file in the user's configuration directory, if any.
+    pub fn default_path() -> Option<PathBuf> {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map(|dir| dir.join(CONFIG_FILE))
+    }
+
+    /// Reads the configuration in the given file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| anyhow::Error::msg(format!("reading {}: {err}", path.display())))?;
+
+        toml::from_str(&content)
+            .map_err(|err| anyhow::Error::msg(format!("parsing {}: {err}", path.display())))
+    }
+
+    /// Sets the configured values as the default ones of the corresponding arguments in the given
+    /// command.
+    pub fn apply(&self, mut command: clap::Command) -> clap::Command {
+        let mut set_default = |id: &str, value: Option<OsStr>| {
+            if let Some(value) = value {
+                command = std::mem::take(&mut command).mut_arg(id, |arg| arg.default_value(value));
+            }
+        };
+
+        set_default(
+            "context",
+            self.context
+                .as_ref()
+                .map(|path| path.to_string_lossy().into_owned().into()),
+        );
+        set_default("extension", self.extension.clone().map(Into::into));
+        set_default(
+            "output",
+            self.output
+                .and_then(|output| output.to_possible_value())
+                .map(|value| value.get_name().to_string().into()),
+        );
+        set_default(
+            "validation_url",
+            self.validation_url.clone().map(Into::into),
+        );
+        set_default("offline", self.offline.map(|flag| flag.to_string().into()));
+        set_default(
+            "no_pager",
+            self.no_pager.map(|flag| flag.to_string().into()),
+        );
+        set_default(
+            "no_color",
+            self.no_color.map(|flag| flag.to_string().into()),
+        );
+        set_default("quiet", self.quiet.map(|flag| flag.to_string().into()));
+
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use clap::{CommandFactory, FromArgMatches, Parser};
+
+    use crate::output::OutputFormat;
+
+    use super::Config;
+
+    /// The environment variable the validation url is read from in these tests.
+    const VALIDATION_URL_ENV: &str = "ALVIDIR_TEST_CONFIG_VALIDATION_URL";
+
+    #[derive(Debug, Parser)]
+    struct Cli {
+        #[arg(default_value = "default", long)]
+        extension: String,
+        #[arg(default_value_t, long, value_enum)]
+        output: OutputFormat,
+        #[arg(long, env = VALIDATION_URL_ENV)]
+        validation_url: Option<String>,
+        #[arg(long, overrides_with = "no_offline")]
+        offline: bool,
+        #[arg(long, overrides_with = "offline")]
+        no_offline: bool,
+    }
+
+    #[test]
+    fn apply_precedence() {
+        struct Test {
+            name: &'static str,
+            config: &'static str,
+            env: Option<&'static str>,
+            args: Vec<&'static str>,
+            extension: &'static str,
+            output: OutputFormat,
+            validation_url: Option<&'static str>,
+            offline: bool,
+        }
+
+        vec![
+            Test {
+                name: "defaults",
+                config: "",
+                env: None,
+                args: vec![],
+                extension: "default",
+                output: OutputFormat::default(),
+                validation_url: None,
+                offline: false,
+            },
+            Test {
+                name: "config over defaults",
+                config: "extension = \"txt\"\noutput = \"json\"\nvalidation_url = \"config\"\noffline = true",
+                env: None,
+                args: vec![],
+                extension: "txt",
+                output: OutputFormat::Json,
+                validation_url: Some("config"),
+                offline: true,
+            },
+            Test {
+                name: "env over config",
+                config: "validation_url = \"config\"",
+                env: Some("env"),
+                args: vec![],
+                extension: "default",
+                output: OutputFormat::default(),
+                validation_url: Some("env"),
+                offline: false,
+            },
+            Test {
+                name: "command line over env and config",
+                config: "extension = \"txt\"\nvalidation_url = \"config\"",
+                env: Some("env"),
+                args: vec!["--extension", "cli", "--validation-url", "cli", "--offline"],
+                extension: "cli",
+                output: OutputFormat::default(),
+                validation_url: Some("cli"),
+                offline: true,
+            },
+            Test {
+                name: "negated flag over config",
+                config: "offline = true",
+                env: None,
+                args: vec!["--no-offline"],
+                extension: "default",
+                output: OutputFormat::default(),
+                validation_url: None,
+                offline: false,
+            },
+            Test {
+                name: "last of a flag and its negation wins",
+                config: "offline = true",
+                env: None,
+                args: vec!["--no-offline", "--offline"],
+                extension: "default",
+                output: OutputFormat::default(),
+                validation_url: None,
+                offline: true,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            match test.env {
+                Some(value) => env::set_var(VALIDATION_URL_ENV, value),
+                None => env::remove_var(VALIDATION_URL_ENV),
+            }
+
+            let config: Config = toml::from_str(test.config).unwrap();
+            let matches = config
+                .apply(Cli::command())
+                .try_get_matches_from(["alvidir"].into_iter().chain(test.args))
+                .unwrap();
+
+            let cli = Cli::from_arg_matches(&matches).unwrap();
+            assert_eq!(cli.extension, test.extension, "{}", test.name);
+            assert_eq!(cli.output, test.output, "{}", test.name);
+            assert_eq!(
+                cli.validation_url.as_deref(),
+                test.validation_url,
+                "{}",
+                test.name
+            );
+            assert_eq!(cli.offline && !cli.no_offline, test.offline, "{}", test.name);
+        });
+
+        env::remove_var(VALIDATION_URL_ENV);
+    }
+}
@@ -0,0 +1,200 @@
+use std::{fs, path::Path};
+
+use alvidir_plugin_interval::circa::Overlap;
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::policy::IdPolicy;
+
+/// The file, relative to the context, in which the constraints documents must satisfy are
+/// configured.
+///
+/// Being part of the context, the same constraints apply to everyone working on its documents.
+pub const CONSTRAINTS_FILE: &str = ".alvidir/constraints.toml";
+
+/// The constraints documents must satisfy, as configured in the [`CONSTRAINTS_FILE`] of a
+/// context.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Constraints {
+    /// The rules every document id must follow.
+    pub policy: IdPolicy,
+    /// The links between documents the check command reports.
+    pub integrity: Integrity,
+    /// The external service document ids are validated by.
+    pub validation: Validation,
+    /// Whether documents may refer to overlapping periods of time.
+    pub simultaneity: Simultaneity,
+}
+
+impl Constraints {
+    /// Reads the constraints in the given file, if it exists, or returns the default ones
+    /// otherwise.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| anyhow::Error::msg(format!("parsing {}: {err}", path.display())))
+    }
+}
+
+/// Whether links between documents must point to existing ones.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Integrity {
+    /// Report links pointing to missing documents.
+    pub enabled: bool,
+    /// Report the links in archived documents as well.
+    pub include_archived: bool,
+}
+
+impl Default for Integrity {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            include_archived: false,
+        }
+    }
+}
+
+/// The external service validating document ids before saving them.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Validation {
+    /// The base url of the service, unless given in the command line.
+    pub url: Option<String>,
+}
+
+/// Whether the periods of time different documents refer to may overlap.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Simultaneity {
+    /// Allow saving a document referring to a period overlapping one from another document.
+    pub allowed: bool,
+    /// How the uncertainty of circa periods is taken into account when overlapping them.
+    pub overlap: PeriodOverlap,
+}
+
+impl Default for Simultaneity {
+    fn default() -> Self {
+        Self {
+            allowed: true,
+            overlap: PeriodOverlap::default(),
+        }
+    }
+}
+
+/// How the uncertainty of circa periods is taken into account when overlapping them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeriodOverlap {
+    /// Periods overlap if they may do so, widening them by their radius.
+    #[default]
+    Possible,
+    /// Periods overlap only if they do so regardless of their radius.
+    Exact,
+}
+
+impl From<PeriodOverlap> for Overlap {
+    fn from(overlap: PeriodOverlap) -> Self {
+        match overlap {
+            PeriodOverlap::Possible => Self::Possible,
+            PeriodOverlap::Exact => Self::Exact,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{Constraints, PeriodOverlap, CONSTRAINTS_FILE};
+
+    #[test]
+    fn load_constraints() {
+        struct Test {
+            name: &'static str,
+            content: Option<&'static str>,
+            loaded: bool,
+            integrity: bool,
+            url: Option<&'static str>,
+            simultaneity: (bool, PeriodOverlap),
+        }
+
+        vec![
+            Test {
+                name: "missing file",
+                content: None,
+                loaded: true,
+                integrity: true,
+                url: None,
+                simultaneity: (true, PeriodOverlap::Possible),
+            },
+            Test {
+                name: "every constraint",
+                content: Some(
+                    "[policy]\nmax_length = 10\n\n\
+                     [integrity]\nenabled = false\n\n\
+                     [validation]\nurl = \"http://registry\"\n\n\
+                     [simultaneity]\nallowed = false\noverlap = \"exact\"\n",
+                ),
+                loaded: true,
+                integrity: false,
+                url: Some("http://registry"),
+                simultaneity: (false, PeriodOverlap::Exact),
+            },
+            Test {
+                name: "unknown overlap",
+                content: Some("[simultaneity]\noverlap = \"partial\"\n"),
+                loaded: false,
+                integrity: true,
+                url: None,
+                simultaneity: (true, PeriodOverlap::Possible),
+            },
+            Test {
+                name: "unknown constraint",
+                content: Some("[timeline]\ngap = 5\n"),
+                loaded: false,
+                integrity: true,
+                url: None,
+                simultaneity: (true, PeriodOverlap::Possible),
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let context = tempfile::tempdir().unwrap();
+            let path = context.path().join(CONSTRAINTS_FILE);
+            if let Some(content) = test.content {
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(&path, content).unwrap();
+            }
+
+            let constraints = Constraints::load(&path);
+            assert_eq!(constraints.is_ok(), test.loaded, "{}", test.name);
+
+            let constraints = constraints.unwrap_or_default();
+            assert_eq!(
+                constraints.integrity.enabled, test.integrity,
+                "{}",
+                test.name
+            );
+            assert_eq!(
+                constraints.validation.url.as_deref(),
+                test.url,
+                "{}",
+                test.name
+            );
+            assert_eq!(
+                (
+                    constraints.simultaneity.allowed,
+                    constraints.simultaneity.overlap
+                ),
+                test.simultaneity,
+                "{}",
+                test.name
+            );
+        });
+    }
+}
@@ -0,0 +1,122 @@
+use std::{path::PathBuf, sync::Arc};
+
+use alvidir::{
+    document::{lazy::LazyDocument, DocumentArchive, DocumentRepository},
+    id::Identify,
+    schema::{
+        ops::save::Save,
+        transaction::{Context, Transaction},
+        Schema,
+    },
+};
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    color::{paint, Role},
+    document::{Document, DocumentLink},
+    error::CliError,
+    output::OutputFormat,
+    progress::Progress,
+};
+
+/// A constraint violation as printed by the doctor command.
+#[derive(Serialize)]
+struct ViolationRecord {
+    /// The id of the document violating the constraint.
+    id: PathBuf,
+    /// The reason why the document violates the constraint.
+    error: String,
+}
+
+/// Check every document against all the constraints in the schema, as if saving it again.
+///
+/// Archived documents are skipped.
+#[derive(Args)]
+pub struct DoctorCommand;
+
+pub struct DoctorCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub document_repo: Arc<DocumentRepo>,
+    /// The format to print results in.
+    pub output: OutputFormat,
+}
+
+impl<DocumentRepo> DoctorCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document> + DocumentArchive,
+{
+    pub fn execute(&self, _: DoctorCommand) -> Result<()> {
+        let archived = self.document_repo.archived();
+        let documents: Vec<_> = self
+            .schema
+            .read()
+            .into_iter()
+            .filter(|document| !archived.contains(document.id()))
+            .cloned()
+            .collect();
+        let progress = Progress::new("checking", documents.len());
+
+        // Every document is saved through the schema, so all triggers (plugins included) run on
+        // it, but the transaction is discarded before being committed.
+        let mut violations: Vec<ViolationRecord> = documents
+            .into_iter()
+            .filter_map(|document| {
+                progress.inc();
+                let id = document.id().clone();
+                self.schema
+                    .transaction()
+                    .with(|ctx: Context<'_, _>| {
+                        Save::new(document).execute(ctx.transaction())?;
+                        ctx.discard();
+                        Ok(())
+                    })
+                    .err()
+                    .map(|err| ViolationRecord {
+                        id,
+                        error: err.to_string(),
+                    })
+            })
+            .collect();
+
+        drop(progress);
+        violations.extend(
+            self.schema
+                .read()
+                .dangling::<DocumentLink>()
+                .into_iter()
+                .filter(|edge| !archived.contains(&edge.from))
+                .map(|edge| ViolationRecord {
+                    id: edge.from,
+                    error: format!("dangling link to {}", edge.to.display()),
+                }),
+        );
+
+        violations.sort_by(|a, b| a.id.cmp(&b.id));
+        let count = violations.len();
+
+        self.output.print_all(violations, |stdout, record| {
+            writeln!(
+                stdout,
+                "{}: {}",
+                paint(Role::Id, record.id.display()),
+                paint(Role::Error, &record.error)
+            )?;
+            Ok(())
+        })?;
+
+        if count > 0 {
+            return Err(CliError::Violations {
+                count,
+                what: "violations found",
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
@@ -1,27 +1,76 @@
 use std::{
+    collections::BTreeSet,
+    env,
     error::Error,
     fmt::Debug,
-    io::{self, Write},
-    path::PathBuf,
+    fs,
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+    process,
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, LazyLock},
 };
 
 use alvidir::{
-    document::{lazy::LazyDocument, DocumentRepository},
+    deref::TryDeref,
+    document::{
+        lazy::LazyDocument, DocumentArchive, DocumentRepository, DocumentStore, DocumentTrash,
+    },
+    graph::Source,
     id::Identify,
+    property::{Extract, Property},
     schema::{
         ops::{delete::Delete, save::Save},
-        Schema,
+        transaction::Transaction,
+        Error as SchemaError, Schema,
     },
 };
+use alvidir_plugin_interval::{calendar::YearRange, circa::Circa};
 use anyhow::Result;
-use clap::{Args, Subcommand};
+use clap::{Args, Subcommand, ValueEnum};
+use regex::{Captures, Regex};
+use serde::Serialize;
+
+use crate::{
+    color::{paint, Role},
+    error::CliError,
+    filter::DocumentFilter,
+    logging,
+    output::OutputFormat,
+    pager,
+    repository::LocalDocumentRepository,
+    resolve::{resolve_id, Matching},
+    similarity::{Similarity, SimilarityRecord, SimilarityWeights, WeightedSimilarity},
+    stats::human_size,
+    template::{find_template, scaffold, DocumentTemplate},
+    watch::Watcher,
+};
+
+/// The document id standing for a list of ids to be read from the standard input.
+const STDIN_ID: &str = "-";
+
+/// The editor to open documents with if none is set in the environment.
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Matches the wiki-style links from one document to another (e.g. `[[sub/b]]`).
+static DOCUMENT_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\[\[([^\[\]]+)\]\]").expect("pattern should be a valid regular expression")
+});
+
+/// Matches the periods of time a document refers to (e.g. `@(circa 1800..1810 ± 5y)`).
+static DOCUMENT_PERIOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"@\(([^()]+)\)").expect("pattern should be a valid regular expression")
+});
+
+/// A range of years, known only approximately.
+pub type Period = Circa<YearRange>;
 
 /// A file-system document.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Document {
+    /// The path of the document relative to the context, without extension.
     pub path: PathBuf,
+    /// The content of the document.
     pub bytes: Vec<u8>,
 }
 
@@ -33,28 +82,289 @@ impl Identify for Document {
     }
 }
 
+impl Document {
+    /// Returns an iterator over the id of every document this one links to.
+    pub fn links(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        let content = String::from_utf8_lossy(&self.bytes);
+        DOCUMENT_LINK
+            .captures_iter(&content)
+            .map(|captures| PathBuf::from(captures[1].trim()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Returns an iterator over every period of time this document refers to, skipping those
+    /// that are not a valid [`Period`].
+    pub fn periods(&self) -> impl Iterator<Item = Period> + '_ {
+        let content = String::from_utf8_lossy(&self.bytes);
+        DOCUMENT_PERIOD
+            .captures_iter(&content)
+            .filter_map(|captures| captures[1].parse().ok())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Returns the content of the document with the target of each link replaced by the output
+    /// of the given closure, if any.
+    ///
+    /// Contents that are not valid UTF-8 are returned as is.
+    pub fn replace_links<F>(&self, f: F) -> Vec<u8>
+    where
+        F: Fn(&Path) -> Option<PathBuf>,
+    {
+        let Ok(content) = std::str::from_utf8(&self.bytes) else {
+            return self.bytes.clone();
+        };
+
+        DOCUMENT_LINK
+            .replace_all(content, |captures: &Captures| {
+                match f(Path::new(captures[1].trim())) {
+                    Some(target) => format!("[[{}]]", target.display()),
+                    None => captures[0].to_string(),
+                }
+            })
+            .into_owned()
+            .into_bytes()
+    }
+}
+
+/// A document as printed by the listing commands.
+#[derive(Serialize)]
+pub struct DocumentRecord<'a, D> {
+    /// The id of the document.
+    pub id: &'a PathBuf,
+    /// The document itself, for those outputs rendering it.
+    #[serde(skip)]
+    pub document: &'a D,
+}
+
+/// A document as printed by the show command.
+#[derive(Serialize)]
+struct DocumentDetailRecord {
+    /// The id of the document.
+    id: PathBuf,
+    /// The size of the document's content, in bytes.
+    size: u64,
+    /// The id of every document this one links to, in order of appearance.
+    links: Vec<PathBuf>,
+    /// The id of every document linking to this one.
+    backlinks: Vec<PathBuf>,
+    /// The content of the document.
+    content: String,
+}
+
+/// A document in the trash as printed by the listing commands.
+#[derive(Serialize)]
+struct TrashedRecord {
+    /// The id of the document.
+    id: PathBuf,
+}
+
+/// A link from a document to the one with the given id.
+pub struct DocumentLink(PathBuf);
+
+impl Identify for DocumentLink {
+    type Id = PathBuf;
+
+    fn id(&self) -> &Self::Id {
+        &self.0
+    }
+}
+
+impl<DocumentRepo> Property<LazyDocument<DocumentRepo>> for DocumentLink
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn all(document: &LazyDocument<DocumentRepo>) -> Vec<Self> {
+        let Some(document) = document.try_deref() else {
+            return Vec::default();
+        };
+
+        document.links().map(DocumentLink).collect()
+    }
+}
+
+/// Extracts the id of documents as it is displayed to the user.
+pub struct DocumentIdExtractor;
+
+impl<DocumentRepo> Extract<LazyDocument<DocumentRepo>> for DocumentIdExtractor
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    type Target = String;
+
+    fn all(&self, document: &LazyDocument<DocumentRepo>) -> Vec<Self::Target> {
+        vec![document.id().display().to_string()]
+    }
+}
+
+/// Extracts the periods of time documents refer to.
+pub struct DocumentPeriodExtractor;
+
+impl<DocumentRepo> Extract<LazyDocument<DocumentRepo>> for DocumentPeriodExtractor
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    type Target = Period;
+
+    fn all(&self, document: &LazyDocument<DocumentRepo>) -> Vec<Self::Target> {
+        let Some(document) = document.try_deref() else {
+            return Vec::default();
+        };
+
+        document.periods().collect()
+    }
+}
+
+/// The field documents are sorted by when listing them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DocumentSortField {
+    /// The id of the document.
+    #[default]
+    Id,
+    /// The file name of the document.
+    Name,
+    /// The size of the document's content, from the smallest to the largest.
+    Size,
+}
+
+/// The page of a listing to print.
+#[derive(Args)]
+struct PageArgs {
+    /// The maximum amount of items to print.
+    #[arg(long)]
+    limit: Option<usize>,
+    /// The amount of items to skip before printing any.
+    #[arg(long, default_value_t = 0)]
+    offset: usize,
+}
+
+impl PageArgs {
+    /// Returns the items of the given iterator in the page.
+    fn apply<T>(&self, items: impl Iterator<Item = T>) -> impl Iterator<Item = T> {
+        items
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+    }
+}
+
+#[derive(Args)]
+struct DocumentListArgs {
+    /// The template to render each document with (e.g. '{{id}} {{name}}').
+    #[arg(long)]
+    format: Option<String>,
+    /// Print only the id of each document, one per line.
+    #[arg(long, conflicts_with = "format")]
+    ids: bool,
+    /// The expression documents must match (e.g. 'id~"sub/" AND link=a').
+    #[arg(long = "where")]
+    filter: Option<String>,
+    /// The field to sort documents by.
+    #[arg(long, default_value_t, value_enum)]
+    sort: DocumentSortField,
+    #[command(flatten)]
+    page: PageArgs,
+    /// Keep printing the list again every time a document changes, until interrupted.
+    #[arg(long)]
+    watch: bool,
+    /// List archived documents too.
+    #[arg(long)]
+    archived: bool,
+}
+
+#[derive(Args)]
+struct DocumentMergeArgs {
+    /// The id of the document to merge into this one.
+    id: PathBuf,
+}
+
+#[derive(Args)]
+struct DocumentRenameArgs {
+    /// The new id of the document.
+    id: PathBuf,
+}
+
+#[derive(Args)]
+struct DocumentSplitArgs {
+    /// The id of the new document.
+    id: PathBuf,
+    /// The line the new document starts at, counting from 1.
+    #[arg(long)]
+    at: usize,
+    /// Append a link to the new document at the end of the split one.
+    #[arg(long)]
+    link: bool,
+}
+
 #[derive(Args)]
 struct DocumentSaveArgs {
     /// The content of the node.
     content: Option<String>,
+    /// The template to render the content from (see the template command).
+    #[arg(long, conflicts_with = "content")]
+    template: Option<PathBuf>,
+    /// Create an empty document for each link to a document that does not exist.
+    #[arg(long)]
+    create_missing: bool,
+}
+
+#[derive(Subcommand)]
+enum DocumentTrashSubCommand {
+    /// List all documents in the trash.
+    #[command(alias("ls"))]
+    List {
+        /// Print only the id of each document, one per line.
+        #[arg(long)]
+        ids: bool,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Remove permanently all documents in the trash.
+    Purge,
 }
 
 #[derive(Subcommand)]
 #[clap(subcommand_negates_reqs = true, subcommand_precedence_over_arg = true)]
 enum DocumentSubCommand {
-    /// Delete a document.
+    /// Hide a document from listings and constraint checks, while keeping it linkable.
+    Archive,
+    /// Delete a document, moving it into the trash.
     Delete,
+    /// Edit the content of a document in the user's editor ($VISUAL or $EDITOR).
+    Edit,
     /// List all documents.
     #[command(alias("ls"))]
-    List,
+    List(DocumentListArgs),
+    /// Merge another document into this one, redirecting the links pointing to the other one
+    /// here and moving it into the trash.
+    Merge(DocumentMergeArgs),
+    /// Change the id of a document, updating the links pointing to it.
+    Rename(DocumentRenameArgs),
+    /// Restore a document from the trash.
+    Restore,
     /// Save a document.
     Save(DocumentSaveArgs),
+    /// Print a document along with its links and the documents linking to it.
+    Show,
+    /// Move the content of a document from a given line on into a new one.
+    Split(DocumentSplitArgs),
+    /// List the documents that are the most similar to a document.
+    Similar {
+        /// The maximum amount of documents to show.
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Manage deleted documents.
+    #[command(subcommand)]
+    Trash(DocumentTrashSubCommand),
+    /// Make an archived document visible again.
+    Unarchive,
 }
 
 /// Manage documents in the graph.
 #[derive(Args)]
 pub struct DocumentCommand {
-    /// The id of the document.
+    /// The id of the document, or "-" to read one id per line from stdin.
     id: Option<String>,
     /// The action to perform.
     #[command(subcommand)]
@@ -68,48 +378,1049 @@ where
 {
     pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
     pub document_repo: Arc<DocumentRepo>,
+    /// The format to print results in.
+    pub output: OutputFormat,
+    /// The file in which the similarity weights are configured.
+    pub similarity_file: PathBuf,
+    /// The local repository to watch for changes made by other processes.
+    pub local_repo: Arc<LocalDocumentRepository>,
+    /// The repository in which the templates of new documents are kept.
+    pub template_repo: Arc<LocalDocumentRepository>,
 }
 
 impl<DocumentRepo> DocumentCli<DocumentRepo>
 where
-    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+    DocumentRepo: 'static
+        + DocumentRepository<Document = Document>
+        + DocumentStore
+        + DocumentTrash
+        + DocumentArchive,
     DocumentRepo::Document: Debug + Clone,
     <DocumentRepo::Document as Identify>::Id: Ord + Clone + FromStr + Debug,
     <<DocumentRepo::Document as Identify>::Id as FromStr>::Err: 'static + Error + Sync + Send,
 {
     pub fn execute(&self, command: DocumentCommand) -> Result<()> {
-        let document_id = || {
-            command
+        let document_ids = || -> Result<Vec<<DocumentRepo::Document as Identify>::Id>> {
+            let id = command
                 .id
-                .map(|id| <DocumentRepo::Document as Identify>::Id::from_str(&id))
-                .transpose()
-                .map_err(anyhow::Error::new)?
-                .ok_or(anyhow::Error::msg("node id must be set"))
+                .as_deref()
+                .ok_or(anyhow::Error::msg("node id must be set"))?;
+
+            let parse = <DocumentRepo::Document as Identify>::Id::from_str;
+            if id != STDIN_ID {
+                return Ok(vec![parse(id)?]);
+            }
+
+            let mut ids = Vec::default();
+            for line in io::stdin().lines() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    ids.push(parse(line.trim())?);
+                }
+            }
+
+            Ok(ids)
         };
 
         match command.subcommand {
+            DocumentSubCommand::Archive => {
+                for document_id in self.resolve_ids(document_ids()?, Matching::Strict)? {
+                    self.document_repo.archive(&document_id)?;
+                    logging::audit("archive", document_id.display());
+                }
+            }
             DocumentSubCommand::Delete => {
-                Delete::new(document_id()?).execute(self.schema.transaction())?
+                for document_id in self.resolve_ids(document_ids()?, Matching::Strict)? {
+                    Delete::new(document_id.clone()).execute(self.schema.transaction())?;
+                    self.document_repo.trash(&document_id)?;
+                    if self.document_repo.archived().contains(&document_id) {
+                        self.document_repo.unarchive(&document_id)?;
+                    }
+
+                    logging::audit("delete", document_id.display());
+                }
+            }
+            DocumentSubCommand::Edit => {
+                let [document_id] =
+                    <[_; 1]>::try_from(self.resolve_ids(document_ids()?, Matching::Loose)?)
+                        .map_err(|_| {
+                            anyhow::Error::msg("a single document can be edited at once")
+                        })?;
+
+                self.edit(&document_id)?;
+            }
+            DocumentSubCommand::List(args) if args.watch => self.watch(&args)?,
+            DocumentSubCommand::List(args) => self.list(&args)?,
+            DocumentSubCommand::Merge(args) => {
+                let [document_id] =
+                    <[_; 1]>::try_from(self.resolve_ids(document_ids()?, Matching::Strict)?)
+                        .map_err(|_| {
+                            anyhow::Error::msg("a single document can be merged into at once")
+                        })?;
+
+                let other_id = self.resolve_ids(vec![args.id], Matching::Strict)?.remove(0);
+
+                self.relocate(&other_id, document_id, true)?;
+            }
+            DocumentSubCommand::Rename(args) => {
+                let [document_id] =
+                    <[_; 1]>::try_from(self.resolve_ids(document_ids()?, Matching::Strict)?)
+                        .map_err(|_| {
+                            anyhow::Error::msg("a single document can be renamed at once")
+                        })?;
+
+                self.relocate(&document_id, args.id, false)?;
             }
-            DocumentSubCommand::List => {
-                let mut stdout = io::stdout().lock();
-                self.schema
-                    .read()
-                    .into_iter()
-                    .for_each(|node| writeln!(stdout, "{:?}", node.id()).unwrap());
+            DocumentSubCommand::Restore => {
+                let trashed = self.document_repo.trashed();
+                for document_id in document_ids()? {
+                    let document_id = resolve_id(
+                        &document_id,
+                        trashed.iter().map(PathBuf::as_path),
+                        Matching::Loose,
+                    )?;
+                    self.document_repo.restore(&document_id)?;
+
+                    let document =
+                        LazyDocument::builder(self.document_repo.clone())(document_id.clone());
+                    Save::new(document).execute(self.schema.transaction())?;
+                    logging::audit("restore", document_id.display());
+                }
             }
             DocumentSubCommand::Save(args) => {
-                let document_id = document_id()?;
-                let document = Document {
-                    path: document_id.clone(),
-                    bytes: args.content.map(|s| s.into_bytes()).unwrap_or_default(),
-                };
+                let template = args
+                    .template
+                    .map(|name| find_template(&self.template_repo, name))
+                    .transpose()?;
+
+                let bytes = args.content.map(|s| s.into_bytes()).unwrap_or_default();
+                for document_id in document_ids()? {
+                    let bytes = match &template {
+                        Some(template) => scaffold(template, &document_id)?.into_bytes(),
+                        None => bytes.clone(),
+                    };
+
+                    let document = Document {
+                        path: document_id.clone(),
+                        bytes,
+                    };
+
+                    // Missing documents are looked up and created in the same transaction, so no
+                    // other save can create them in between.
+                    let mut missing = Vec::new();
+                    self.schema.transaction().with(|ctx| {
+                        if args.create_missing {
+                            missing = document
+                                .links()
+                                .filter(|link| *link != document_id && !ctx.contains(link))
+                                .collect::<BTreeSet<_>>()
+                                .into_iter()
+                                .map(|path| Document {
+                                    path,
+                                    bytes: Vec::new(),
+                                })
+                                .collect();
+                        }
+
+                        for document in &missing {
+                            Save::new(LazyDocument::new(
+                                self.document_repo.clone(),
+                                document.clone(),
+                            ))
+                            .execute(ctx.transaction())?;
+                        }
+
+                        Save::new(LazyDocument::new(
+                            self.document_repo.clone(),
+                            document.clone(),
+                        ))
+                        .execute(ctx.transaction())
+                    })?;
+
+                    for document in &missing {
+                        self.document_repo.save(document)?;
+                        logging::audit("save", document.path.display());
+                    }
+
+                    self.document_repo.save(&document)?;
+                    logging::audit("save", document_id.display());
+                }
+            }
+            DocumentSubCommand::Show => {
+                let [document_id] =
+                    <[_; 1]>::try_from(self.resolve_ids(document_ids()?, Matching::Loose)?)
+                        .map_err(|_| {
+                            anyhow::Error::msg("a single document can be shown at once")
+                        })?;
+
+                self.show(&document_id)?;
+            }
+            DocumentSubCommand::Similar { limit } => {
+                let [document_id] =
+                    <[_; 1]>::try_from(self.resolve_ids(document_ids()?, Matching::Loose)?)
+                        .map_err(|_| {
+                            anyhow::Error::msg("similar documents are listed for a single document")
+                        })?;
+
+                self.similar(&document_id, limit)?;
+            }
+            DocumentSubCommand::Split(args) => {
+                let [document_id] =
+                    <[_; 1]>::try_from(self.resolve_ids(document_ids()?, Matching::Strict)?)
+                        .map_err(|_| {
+                            anyhow::Error::msg("a single document can be split at once")
+                        })?;
 
-                Save::new(LazyDocument::new(self.document_repo.clone(), document))
-                    .execute(self.schema.transaction())?;
+                self.split(&document_id, args)?;
+            }
+            DocumentSubCommand::Trash(DocumentTrashSubCommand::List { ids, page }) => {
+                let mut trashed = self.document_repo.trashed();
+                trashed.sort();
+
+                let records = page
+                    .apply(trashed.into_iter())
+                    .map(|id| TrashedRecord { id });
+
+                self.output.print_all(records, |stdout, record| {
+                    if ids {
+                        writeln!(stdout, "{}", record.id.display())?;
+                    } else {
+                        writeln!(
+                            stdout,
+                            "{}",
+                            paint(Role::Id, format_args!("{:?}", record.id))
+                        )?;
+                    }
+
+                    Ok(())
+                })?;
+            }
+            DocumentSubCommand::Trash(DocumentTrashSubCommand::Purge) => {
+                self.document_repo.purge()?;
+            }
+            DocumentSubCommand::Unarchive => {
+                let archived = self.document_repo.archived();
+                for document_id in document_ids()? {
+                    let document_id = resolve_id(
+                        &document_id,
+                        archived.iter().map(PathBuf::as_path),
+                        Matching::Loose,
+                    )?;
+                    self.document_repo.unarchive(&document_id)?;
+                    logging::audit("unarchive", document_id.display());
+                }
             }
         };
 
         Ok(())
     }
+
+    /// Changes the id of the given document to the new one, rewriting every link pointing to it.
+    ///
+    /// If merging, the new id must belong to an existing document, whose content is followed by
+    /// the one of the given document. Otherwise, it must not exist. Either way, the document
+    /// under its previous id is moved into the trash.
+    fn relocate(&self, document_id: &PathBuf, new_id: PathBuf, merge: bool) -> Result<()> {
+        if document_id == &new_id {
+            return Err(anyhow::Error::msg(format!(
+                "document {} cannot be relocated into itself",
+                new_id.display()
+            )));
+        }
+
+        let graph = self.schema.read();
+        let target = graph
+            .get(&new_id)
+            .and_then(|document| document.try_deref().cloned());
+
+        match (&target, merge) {
+            (Some(_), false) => return Err(CliError::DocumentExists(new_id.clone()).into()),
+            (None, true) => return Err(CliError::DocumentNotFound(new_id.clone()).into()),
+            _ => {}
+        }
+
+        let Some(relocated) = graph
+            .get(document_id)
+            .and_then(|document| document.try_deref().cloned())
+        else {
+            return Err(CliError::DocumentNotFound(document_id.clone()).into());
+        };
+
+        let relink = |document: &Document| {
+            document.replace_links(|target| (target == document_id).then(|| new_id.clone()))
+        };
+
+        let mut bytes = target.as_ref().map(relink).unwrap_or_default();
+        if !bytes.is_empty() && !bytes.ends_with(b"\n") {
+            bytes.push(b'\n');
+        }
+
+        bytes.extend(relink(&relocated));
+        let mut documents = vec![Document {
+            path: new_id.clone(),
+            bytes,
+        }];
+
+        documents.extend(
+            graph
+                .into_iter()
+                .filter(|document| document.id() != document_id && document.id() != &new_id)
+                .filter_map(|document| document.try_deref())
+                .filter_map(|document| {
+                    let bytes = relink(document);
+                    (bytes != document.bytes).then(|| Document {
+                        path: document.path.clone(),
+                        bytes,
+                    })
+                }),
+        );
+
+        drop(graph);
+
+        self.schema.transaction().with(|ctx| {
+            Delete::new(document_id.clone()).execute(ctx.transaction())?;
+            for document in &documents {
+                Save::new(LazyDocument::new(
+                    self.document_repo.clone(),
+                    document.clone(),
+                ))
+                .execute(ctx.transaction())?;
+            }
+
+            Ok(())
+        })?;
+
+        for document in &documents {
+            self.document_repo.save(document)?;
+        }
+
+        self.document_repo.trash(document_id)?;
+
+        // A renamed document keeps being archived, while a merged one takes the state of the
+        // document it is merged into.
+        if self.document_repo.archived().contains(document_id) {
+            self.document_repo.unarchive(document_id)?;
+            if !merge {
+                self.document_repo.archive(&new_id)?;
+            }
+        }
+
+        logging::audit(
+            if merge { "merge" } else { "rename" },
+            document_id.display(),
+        );
+
+        for document in &documents {
+            logging::audit("save", document.path.display());
+        }
+
+        Ok(())
+    }
+
+    /// Prints the documents in the graph as told by the given arguments.
+    fn list(&self, args: &DocumentListArgs) -> Result<()> {
+        let template = args
+            .format
+            .as_deref()
+            .map(DocumentTemplate::new)
+            .transpose()?;
+        let filter = args
+            .filter
+            .as_deref()
+            .map(DocumentFilter::new)
+            .transpose()?;
+
+        let archived = if args.archived {
+            Vec::default()
+        } else {
+            self.document_repo.archived()
+        };
+
+        let graph = self.schema.read();
+        let mut documents: Vec<_> = graph
+            .into_iter()
+            .filter(|document| !archived.contains(document.id()))
+            .filter(|document| {
+                filter
+                    .as_ref()
+                    .is_none_or(|filter| filter.matches(*document))
+            })
+            .collect();
+
+        match args.sort {
+            DocumentSortField::Id => documents.sort_by_key(|document| document.id()),
+            DocumentSortField::Name => documents.sort_by_key(|document| document.id().file_name()),
+            DocumentSortField::Size => documents.sort_by_cached_key(|document| {
+                document
+                    .try_deref()
+                    .map(|document| document.bytes.len())
+                    .unwrap_or_default()
+            }),
+        }
+
+        let records = args
+            .page
+            .apply(documents.into_iter())
+            .map(|document| DocumentRecord {
+                id: document.id(),
+                document,
+            });
+
+        self.output.print_all(records, |stdout, record| {
+            match &template {
+                Some(template) => writeln!(stdout, "{}", template.render(record.document)?)?,
+                None if args.ids => writeln!(stdout, "{}", record.id.display())?,
+                None => writeln!(
+                    stdout,
+                    "{}",
+                    paint(Role::Id, format_args!("{:?}", record.id))
+                )?,
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Prints the documents in the graph as told by the given arguments, and does it again every
+    /// time a document changes, until interrupted.
+    fn watch(&self, args: &DocumentListArgs) -> Result<()> {
+        // The pager would hold the first listing until closed.
+        pager::disable();
+
+        let mut watcher = Watcher::new(self.local_repo.clone());
+        loop {
+            if io::stdout().is_terminal() {
+                // Clears the screen and moves the cursor home.
+                write!(io::stdout(), "\x1b[2J\x1b[H")?;
+            }
+
+            self.list(args)?;
+            io::stdout().flush()?;
+
+            let changes = watcher.wait();
+            for document_id in changes.removed {
+                match Delete::new(document_id.clone()).execute(self.schema.transaction()) {
+                    Ok(_) | Err(SchemaError::Noop) => {}
+                    Err(err) => {
+                        tracing::warn!(error = err.to_string(), id = ?document_id, "removing document")
+                    }
+                }
+            }
+
+            for document_id in changes.saved {
+                let document =
+                    LazyDocument::builder(self.document_repo.clone())(document_id.clone());
+                if let Err(err) = Save::new(document).execute(self.schema.transaction()) {
+                    tracing::warn!(error = err.to_string(), id = ?document_id, "reloading document");
+                }
+            }
+        }
+    }
+
+    /// Moves the content of the given document from the given line on into a new document.
+    ///
+    /// Both documents are saved in a single transaction, so none is if any violates a constraint.
+    fn split(&self, document_id: &PathBuf, args: DocumentSplitArgs) -> Result<()> {
+        let graph = self.schema.read();
+        if graph.contains(&args.id) {
+            return Err(CliError::DocumentExists(args.id.clone()).into());
+        }
+
+        let Some(document) = graph
+            .get(document_id)
+            .and_then(|document| document.try_deref().cloned())
+        else {
+            return Err(CliError::DocumentNotFound(document_id.clone()).into());
+        };
+
+        drop(graph);
+
+        let lines: Vec<&[u8]> = document.bytes.split_inclusive(|&b| b == b'\n').collect();
+        if lines.len() < 2 {
+            return Err(anyhow::Error::msg(format!(
+                "document {} has less than two lines",
+                document_id.display()
+            )));
+        }
+
+        if args.at < 2 || args.at > lines.len() {
+            return Err(anyhow::Error::msg(format!(
+                "line must be in between 2 and {}",
+                lines.len()
+            )));
+        }
+
+        let mut head = lines[..args.at - 1].concat();
+        if args.link {
+            head.extend(format!("[[{}]]\n", args.id.display()).into_bytes());
+        }
+
+        let documents = [
+            Document {
+                path: document_id.clone(),
+                bytes: head,
+            },
+            Document {
+                path: args.id,
+                bytes: lines[args.at - 1..].concat(),
+            },
+        ];
+
+        self.schema.transaction().with(|ctx| {
+            for document in &documents {
+                Save::new(LazyDocument::new(
+                    self.document_repo.clone(),
+                    document.clone(),
+                ))
+                .execute(ctx.transaction())?;
+            }
+
+            Ok(())
+        })?;
+
+        for document in &documents {
+            self.document_repo.save(document)?;
+            logging::audit("save", document.path.display());
+        }
+
+        Ok(())
+    }
+
+    /// Opens the content of the given document in the user's editor, saving it once the editor
+    /// exits if it has changed.
+    ///
+    /// The edited content is kept in a temporary file if it cannot be saved, so no change is lost.
+    fn edit(&self, document_id: &PathBuf) -> Result<()> {
+        let Some(document) = self
+            .schema
+            .read()
+            .get(document_id)
+            .and_then(|document| document.try_deref().cloned())
+        else {
+            return Err(CliError::DocumentNotFound(document_id.clone()).into());
+        };
+
+        let editor = env::var("VISUAL")
+            .or_else(|_| env::var("EDITOR"))
+            .unwrap_or_else(|_| DEFAULT_EDITOR.to_string());
+
+        // The editor may come with arguments (e.g. 'code --wait').
+        let mut words = editor.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| anyhow::Error::msg("editor must be set"))?;
+
+        let name = document_id
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let tmp = env::temp_dir().join(format!("alvidir-{}-{name}", process::id()));
+        fs::write(&tmp, &document.bytes)?;
+
+        let status = process::Command::new(program)
+            .args(words)
+            .arg(&tmp)
+            .status()?;
+
+        if !status.success() {
+            fs::remove_file(&tmp)?;
+            return Err(anyhow::Error::msg(format!("editor exited with {status}")));
+        }
+
+        let bytes = fs::read(&tmp)?;
+        if bytes == document.bytes {
+            fs::remove_file(&tmp)?;
+            return Ok(());
+        }
+
+        let edited = Document {
+            path: document_id.clone(),
+            bytes,
+        };
+
+        let result = Save::new(LazyDocument::new(
+            self.document_repo.clone(),
+            edited.clone(),
+        ))
+        .execute(self.schema.transaction())
+        .and_then(|_| self.document_repo.save(&edited));
+
+        if let Err(err) = result {
+            return Err(anyhow::Error::msg(format!(
+                "{err}: changes kept in {}",
+                tmp.display()
+            )));
+        }
+
+        fs::remove_file(&tmp)?;
+        logging::audit("save", document_id.display());
+        Ok(())
+    }
+
+    /// Prints the document with the given id in detail.
+    fn show(&self, document_id: &PathBuf) -> Result<()> {
+        let graph = self.schema.read();
+        let Some(document) = graph
+            .get(document_id)
+            .and_then(|document| document.try_deref().cloned())
+        else {
+            return Err(CliError::DocumentNotFound(document_id.clone()).into());
+        };
+
+        let mut links = Vec::default();
+        for link in document.links() {
+            if !links.contains(&link) {
+                links.push(link);
+            }
+        }
+
+        let mut backlinks: Vec<PathBuf> = graph
+            .into_iter()
+            .filter(|other| other.id() != document_id)
+            .filter(|other| {
+                other
+                    .try_deref()
+                    .is_some_and(|other| other.links().any(|link| &link == document_id))
+            })
+            .map(|other| other.id().clone())
+            .collect();
+
+        backlinks.sort();
+
+        // Links to missing documents are highlighted, as reported by the check command.
+        let dangling: Vec<bool> = links.iter().map(|link| !graph.contains(link)).collect();
+        drop(graph);
+
+        let record = DocumentDetailRecord {
+            id: document.path.clone(),
+            size: document.bytes.len() as u64,
+            links,
+            backlinks,
+            content: String::from_utf8_lossy(&document.bytes).into_owned(),
+        };
+
+        self.output.print(record, |stdout, record| {
+            let list = |ids: &mut dyn Iterator<Item = String>| {
+                let ids: Vec<String> = ids.collect();
+                if ids.is_empty() {
+                    return paint(Role::Muted, "none");
+                }
+
+                ids.join(", ")
+            };
+
+            writeln!(
+                stdout,
+                "id:        {}",
+                paint(Role::Id, record.id.display())
+            )?;
+            writeln!(stdout, "size:      {}", human_size(record.size))?;
+            writeln!(
+                stdout,
+                "links:     {}",
+                list(
+                    &mut record.links.iter().zip(&dangling).map(|(link, dangling)| {
+                        let role = if *dangling { Role::Error } else { Role::Id };
+                        paint(role, link.display())
+                    })
+                )
+            )?;
+            writeln!(
+                stdout,
+                "backlinks: {}",
+                list(
+                    &mut record
+                        .backlinks
+                        .iter()
+                        .map(|link| paint(Role::Id, link.display()))
+                )
+            )?;
+
+            writeln!(stdout)?;
+            write!(stdout, "{}", record.content)?;
+            if !record.content.is_empty() && !record.content.ends_with('\n') {
+                writeln!(stdout)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Prints, at most, the given amount of documents that are the most similar to the given one,
+    /// from the most to the least similar.
+    fn similar(&self, document_id: &PathBuf, limit: usize) -> Result<()> {
+        let similarity: WeightedSimilarity<LazyDocument<DocumentRepo>> =
+            SimilarityWeights::load(&self.similarity_file)?.into();
+
+        let graph = self.schema.read();
+        let Some(document) = graph.get(document_id) else {
+            return Err(CliError::DocumentNotFound(document_id.clone()).into());
+        };
+
+        let mut records: Vec<_> = graph
+            .into_iter()
+            .filter(|other| other.id() != document_id)
+            .map(|other| SimilarityRecord {
+                id: other.id().clone(),
+                similarity: similarity.score(&document, other),
+            })
+            .collect();
+
+        records.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+        records.truncate(limit);
+
+        self.output.print_all(records, |stdout, record| {
+            writeln!(
+                stdout,
+                "{}  {}",
+                paint(Role::Muted, format_args!("{:.3}", record.similarity)),
+                paint(Role::Id, format_args!("{:?}", record.id))
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Returns the ids of the existing documents the given ones stand for, as matched by
+    /// [`resolve_id`].
+    fn resolve_ids(&self, ids: Vec<PathBuf>, matching: Matching) -> Result<Vec<PathBuf>> {
+        let graph = self.schema.read();
+        ids.iter()
+            .map(|id| {
+                resolve_id(
+                    id,
+                    graph.into_iter().map(|document| document.id().as_path()),
+                    matching,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        fs,
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+
+    use alvidir::document::DocumentArchive;
+    use anyhow::Result;
+    use clap::Parser;
+
+    use crate::{
+        output::OutputFormat,
+        repository::{fixtures::local_schema, LocalDocumentRepository},
+        similarity::SIMILARITY_FILE,
+        template::TEMPLATES_DIR,
+    };
+
+    use super::{DocumentCli, DocumentCommand};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        command: DocumentCommand,
+    }
+
+    /// Runs the document command with the given arguments in a context holding the given
+    /// documents, returning its result along with the content of every document afterwards.
+    fn run(documents: &[(&str, &str)], args: &[&str]) -> (Result<()>, BTreeMap<String, String>) {
+        let (result, contents, _) = run_archived(documents, &[], args);
+        (result, contents)
+    }
+
+    /// Same as [`run`], but archiving the given documents beforehand and also returning the
+    /// archived ones afterwards.
+    fn run_archived(
+        documents: &[(&str, &str)],
+        archived: &[&str],
+        args: &[&str],
+    ) -> (Result<()>, BTreeMap<String, String>, Vec<String>) {
+        let context = tempfile::tempdir().unwrap();
+        for (id, content) in documents {
+            let path = context.path().join(id).with_extension("md");
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let (document_repo, schema) = local_schema(context.path());
+        for id in archived {
+            document_repo.archive(&PathBuf::from(id)).unwrap();
+        }
+
+        let cli = DocumentCli {
+            schema,
+            document_repo: document_repo.clone(),
+            output: OutputFormat::default(),
+            similarity_file: context.path().join(SIMILARITY_FILE),
+            local_repo: document_repo.clone(),
+            template_repo: Arc::new(LocalDocumentRepository {
+                context: context.path().join(TEMPLATES_DIR),
+                extension: "md".to_string(),
+            }),
+        };
+
+        let result = Cli::try_parse_from(["doc"].iter().chain(args))
+            .map_err(anyhow::Error::from)
+            .and_then(|args| cli.execute(args.command));
+
+        let archived = document_repo
+            .archived()
+            .into_iter()
+            .map(|id| id.display().to_string())
+            .collect();
+
+        (result, contents(&document_repo.context), archived)
+    }
+
+    /// Returns the content of every document in the given context, by id.
+    fn contents(context: &Path) -> BTreeMap<String, String> {
+        let document_repo = LocalDocumentRepository {
+            context: context.to_path_buf(),
+            extension: "md".to_string(),
+        };
+
+        document_repo
+            .ids()
+            .map(|id| {
+                let content = fs::read_to_string(context.join(&id).with_extension("md")).unwrap();
+                (id.display().to_string(), content)
+            })
+            .collect()
+    }
+
+    /// Returns the given documents by id.
+    fn documents(documents: &[(&str, &str)]) -> BTreeMap<String, String> {
+        documents
+            .iter()
+            .map(|(id, content)| (id.to_string(), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn relocate_documents() {
+        struct Test {
+            name: &'static str,
+            args: Vec<&'static str>,
+            ok: bool,
+            want: Vec<(&'static str, &'static str)>,
+        }
+
+        let existing = [
+            ("a", "see [[b]]"),
+            ("b", "b links [[b]] and [[sub/c]]"),
+            ("sub/c", "[[b]] and [[a]]"),
+        ];
+
+        vec![
+            Test {
+                name: "rename rewrites links",
+                args: vec!["b", "rename", "d"],
+                ok: true,
+                want: vec![
+                    ("a", "see [[d]]"),
+                    ("d", "b links [[d]] and [[sub/c]]"),
+                    ("sub/c", "[[d]] and [[a]]"),
+                ],
+            },
+            Test {
+                name: "rename into an existing document",
+                args: vec!["b", "rename", "a"],
+                ok: false,
+                want: existing.to_vec(),
+            },
+            Test {
+                name: "rename into itself",
+                args: vec!["b", "rename", "b"],
+                ok: false,
+                want: existing.to_vec(),
+            },
+            Test {
+                name: "merge appends and rewrites links",
+                args: vec!["a", "merge", "b"],
+                ok: true,
+                want: vec![
+                    ("a", "see [[a]]\nb links [[a]] and [[sub/c]]"),
+                    ("sub/c", "[[a]] and [[a]]"),
+                ],
+            },
+            Test {
+                name: "merge a missing document",
+                args: vec!["a", "merge", "missing"],
+                ok: false,
+                want: existing.to_vec(),
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let (result, got) = run(&existing, &test.args);
+            assert_eq!(result.is_ok(), test.ok, "{}: {result:?}", test.name);
+            assert_eq!(got, documents(&test.want), "{}", test.name);
+        });
+    }
+
+    #[test]
+    fn split_documents() {
+        struct Test {
+            name: &'static str,
+            args: Vec<&'static str>,
+            ok: bool,
+            want: Vec<(&'static str, &'static str)>,
+        }
+
+        let existing = [("a", "one\ntwo\nthree\n"), ("b", "single line")];
+
+        vec![
+            Test {
+                name: "split at the last line",
+                args: vec!["a", "split", "c", "--at", "3"],
+                ok: true,
+                want: vec![("a", "one\ntwo\n"), ("b", "single line"), ("c", "three\n")],
+            },
+            Test {
+                name: "split at the second line with a link",
+                args: vec!["a", "split", "c", "--at", "2", "--link"],
+                ok: true,
+                want: vec![
+                    ("a", "one\n[[c]]\n"),
+                    ("b", "single line"),
+                    ("c", "two\nthree\n"),
+                ],
+            },
+            Test {
+                name: "split at the first line",
+                args: vec!["a", "split", "c", "--at", "1"],
+                ok: false,
+                want: existing.to_vec(),
+            },
+            Test {
+                name: "split beyond the last line",
+                args: vec!["a", "split", "c", "--at", "4"],
+                ok: false,
+                want: existing.to_vec(),
+            },
+            Test {
+                name: "split into an existing document",
+                args: vec!["a", "split", "b", "--at", "2"],
+                ok: false,
+                want: existing.to_vec(),
+            },
+            Test {
+                name: "split a single line document",
+                args: vec!["b", "split", "c", "--at", "2"],
+                ok: false,
+                want: existing.to_vec(),
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let (result, got) = run(&existing, &test.args);
+            assert_eq!(result.is_ok(), test.ok, "{}: {result:?}", test.name);
+            assert_eq!(got, documents(&test.want), "{}", test.name);
+        });
+    }
+
+    #[test]
+    fn save_creating_missing_documents() {
+        struct Test {
+            name: &'static str,
+            args: Vec<&'static str>,
+            want: Vec<(&'static str, &'static str)>,
+        }
+
+        let existing = [("a", "a")];
+
+        vec![
+            Test {
+                name: "without create missing",
+                args: vec!["b", "save", "[[a]] and [[c]]"],
+                want: vec![("a", "a"), ("b", "[[a]] and [[c]]")],
+            },
+            Test {
+                name: "with create missing",
+                args: vec![
+                    "b",
+                    "save",
+                    "[[a]], [[c]] and [[sub/d]]",
+                    "--create-missing",
+                ],
+                want: vec![
+                    ("a", "a"),
+                    ("b", "[[a]], [[c]] and [[sub/d]]"),
+                    ("c", ""),
+                    ("sub/d", ""),
+                ],
+            },
+            Test {
+                name: "self links are not created twice",
+                args: vec!["b", "save", "[[b]] and [[c]] [[c]]", "--create-missing"],
+                want: vec![("a", "a"), ("b", "[[b]] and [[c]] [[c]]"), ("c", "")],
+            },
+            Test {
+                name: "existing documents are not overwritten",
+                args: vec!["b", "save", "[[a]]", "--create-missing"],
+                want: vec![("a", "a"), ("b", "[[a]]")],
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let (result, got) = run(&existing, &test.args);
+            assert!(result.is_ok(), "{}: {result:?}", test.name);
+            assert_eq!(got, documents(&test.want), "{}", test.name);
+        });
+    }
+
+    #[test]
+    fn relocate_and_delete_archived_documents() {
+        struct Test {
+            name: &'static str,
+            archived: Vec<&'static str>,
+            args: Vec<&'static str>,
+            want: Vec<&'static str>,
+        }
+
+        let existing = [("a", "a"), ("b", "b"), ("c", "c")];
+
+        vec![
+            Test {
+                name: "renaming an archived document keeps it archived",
+                archived: vec!["b", "c"],
+                args: vec!["b", "rename", "d"],
+                want: vec!["c", "d"],
+            },
+            Test {
+                name: "renaming a visible document keeps it visible",
+                archived: vec!["c"],
+                args: vec!["b", "rename", "d"],
+                want: vec!["c"],
+            },
+            Test {
+                name: "merging an archived document into a visible one",
+                archived: vec!["b"],
+                args: vec!["a", "merge", "b"],
+                want: vec![],
+            },
+            Test {
+                name: "merging a visible document into an archived one",
+                archived: vec!["a"],
+                args: vec!["a", "merge", "b"],
+                want: vec!["a"],
+            },
+            Test {
+                name: "deleting an archived document",
+                archived: vec!["b", "c"],
+                args: vec!["b", "delete"],
+                want: vec!["c"],
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let (result, _, archived) = run_archived(&existing, &test.archived, &test.args);
+            assert!(result.is_ok(), "{}: {result:?}", test.name);
+            assert_eq!(archived, test.want, "{}", test.name);
+        });
+    }
 }
@@ -0,0 +1,175 @@
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+use alvidir::{
+    document::{DocumentArchive, DocumentRepository, DocumentStore, DocumentTrash},
+    id::Identify,
+    schema::{
+        middleware::{Middleware, Next},
+        transaction::Context,
+        Error, Result,
+    },
+};
+
+use crate::document::Document;
+
+/// Whether commands may write into the context, as set by the read-only and dry-run flags.
+///
+/// Documents are protected by decorating their repository, while any other file (e.g. backups or
+/// checksums) must be checked against this mode before being written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WriteMode {
+    /// Writes are performed.
+    #[default]
+    Write,
+    /// Writes are rejected with [`Error::ReadOnly`].
+    ReadOnly,
+    /// Writes are printed into the standard error instead of being performed.
+    DryRun,
+}
+
+impl WriteMode {
+    /// Returns true if, and only if, the given action must be performed.
+    pub fn allows(self, action: fmt::Arguments<'_>) -> Result<bool> {
+        match self {
+            WriteMode::Write => Ok(true),
+            WriteMode::ReadOnly => Err(Error::ReadOnly),
+            WriteMode::DryRun => {
+                report(format_args!("would {action}"));
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// A [`DocumentRepository`] decorator that prints every modification into the standard error
+/// instead of performing it.
+pub struct DryRunRepository<DocumentRepo> {
+    /// The repository being decorated.
+    document_repo: DocumentRepo,
+}
+
+impl<DocumentRepo> DryRunRepository<DocumentRepo> {
+    pub fn new(document_repo: DocumentRepo) -> Self {
+        Self { document_repo }
+    }
+}
+
+impl<DocumentRepo> DocumentRepository for DryRunRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    type Document = Document;
+
+    fn find_by_id(&self, id: &<Self::Document as Identify>::Id) -> Option<Self::Document> {
+        self.document_repo.find_by_id(id)
+    }
+}
+
+impl<DocumentRepo> DocumentStore for DryRunRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    fn save(&self, document: &Self::Document) -> Result<()> {
+        report(format_args!("would save {}", document.path.display()));
+        Ok(())
+    }
+}
+
+impl<DocumentRepo> DocumentTrash for DryRunRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentTrash<Document = Document>,
+{
+    fn trash(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        report(format_args!("would delete {}", id.display()));
+        Ok(())
+    }
+
+    fn restore(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        report(format_args!("would restore {}", id.display()));
+        Ok(())
+    }
+
+    fn purge(&self) -> Result<()> {
+        report(format_args!("would purge the trash"));
+        Ok(())
+    }
+
+    fn trashed(&self) -> Vec<<Self::Document as Identify>::Id> {
+        self.document_repo.trashed()
+    }
+}
+
+impl<DocumentRepo> DocumentArchive for DryRunRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentArchive<Document = Document>,
+{
+    fn archive(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        report(format_args!("would archive {}", id.display()));
+        Ok(())
+    }
+
+    fn unarchive(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        report(format_args!("would unarchive {}", id.display()));
+        Ok(())
+    }
+
+    fn archived(&self) -> Vec<<Self::Document as Identify>::Id> {
+        self.document_repo.archived()
+    }
+}
+
+/// A [`Middleware`] printing into the standard error whether every constraint passed or not, and
+/// so did every transaction as a whole.
+pub struct DryRunReport;
+
+impl<T> Middleware<T> for DryRunReport
+where
+    T: Identify,
+{
+    fn handle(&self, _: &Context<'_, T>, next: Next<'_>) -> Result<()> {
+        let result = next.run();
+        match &result {
+            Ok(_) => report(format_args!("constraints passed")),
+            Err(err) => report(format_args!("constraints failed: {err}")),
+        }
+
+        result
+    }
+
+    fn on_trigger(&self, trigger: &str, result: &Result<()>) {
+        let trigger = short_name(trigger);
+        match result {
+            Ok(_) => report(format_args!("passed {trigger}")),
+            Err(err) => report(format_args!("failed {trigger}: {err}")),
+        }
+    }
+}
+
+/// Returns the given type name without generic arguments nor module path, except for the type
+/// the function belongs to (e.g. `IdPolicyPlugin::before_save`).
+fn short_name(name: &str) -> String {
+    let mut depth = 0;
+    let name: String = name
+        .chars()
+        .filter(|c| {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => return depth == 0,
+            }
+
+            false
+        })
+        .collect();
+
+    let segments: Vec<_> = name.rsplit("::").take(2).collect();
+    segments.into_iter().rev().collect::<Vec<_>>().join("::")
+}
+
+/// Prints the given line into the standard error, ignoring any failure.
+fn report(line: fmt::Arguments<'_>) {
+    let _ = writeln!(io::stderr(), "{line}");
+}
@@ -0,0 +1,95 @@
+use std::{path::PathBuf, sync::Arc};
+
+use alvidir::{
+    document::{lazy::LazyDocument, DocumentRepository},
+    id::Identify,
+    schema::Schema,
+};
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    color::{paint, Role},
+    document::Document,
+    output::OutputFormat,
+    progress::Progress,
+    similarity::{Similarity, SimilarityWeights, WeightedSimilarity},
+};
+
+/// A pair of documents that may be duplicates as printed by the duplicates command.
+#[derive(Serialize)]
+struct DuplicateRecord {
+    /// The id of a document.
+    id: PathBuf,
+    /// The id of the document it may duplicate.
+    other: PathBuf,
+    /// The similarity between both documents, from 0 to 1.
+    similarity: f32,
+}
+
+/// List the pairs of documents that are so similar they may be duplicates, as candidates to be
+/// merged (see 'doc merge').
+///
+/// Similarity is scored as in 'doc similar', with the weights in the similarity file.
+#[derive(Args)]
+pub struct DuplicatesCommand {
+    /// The minimum similarity, from 0 to 1, for two documents to be listed as duplicates.
+    #[arg(long, default_value_t = 0.6)]
+    threshold: f32,
+}
+
+pub struct DuplicatesCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    /// The file in which the similarity weights are configured.
+    pub similarity_file: PathBuf,
+    /// The format to print results in.
+    pub output: OutputFormat,
+}
+
+impl<DocumentRepo> DuplicatesCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    pub fn execute(&self, command: DuplicatesCommand) -> Result<()> {
+        let similarity: WeightedSimilarity<LazyDocument<DocumentRepo>> =
+            SimilarityWeights::load(&self.similarity_file)?.into();
+
+        let graph = self.schema.read();
+        let documents: Vec<_> = graph.into_iter().collect();
+
+        // Each step compares a document with all the ones after it, so pairs are scored once.
+        let progress = Progress::new("comparing", documents.len());
+        let mut records = Vec::new();
+        for (index, document) in documents.iter().enumerate() {
+            progress.inc();
+            for other in &documents[index + 1..] {
+                let score = similarity.score(document, other);
+                if score >= command.threshold {
+                    records.push(DuplicateRecord {
+                        id: document.id().clone(),
+                        other: other.id().clone(),
+                        similarity: score,
+                    });
+                }
+            }
+        }
+
+        drop(progress);
+        records.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+
+        self.output.print_all(records, |stdout, record| {
+            writeln!(
+                stdout,
+                "{}  {} {}",
+                paint(Role::Muted, format_args!("{:.3}", record.similarity)),
+                paint(Role::Id, format_args!("{:?}", record.id)),
+                paint(Role::Id, format_args!("{:?}", record.other))
+            )?;
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// An error the CLI knows the class of.
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error("document {} does not exist", .0.display())]
+    DocumentNotFound(PathBuf),
+    #[error("document {} already exists", .0.display())]
+    DocumentExists(PathBuf),
+    #[error("backup {0} does not exist")]
+    BackupNotFound(u64),
+    #[error("backup {0} already exists")]
+    BackupExists(u64),
+    #[error("template {} does not exist", .0.display())]
+    TemplateNotFound(PathBuf),
+    /// An id standing for several documents.
+    #[error("ambiguous id {}: matches {}", .id.display(), .candidates.join(", "))]
+    AmbiguousId {
+        id: PathBuf,
+        candidates: Vec<String>,
+    },
+    /// An id standing for no document, but similar to others.
+    #[error(
+        "document {} does not exist, did you mean {}?",
+        .id.display(),
+        .suggestions.join(", ")
+    )]
+    InexactId {
+        id: PathBuf,
+        suggestions: Vec<String>,
+    },
+    /// Some documents do not satisfy the constraints of the schema.
+    #[error("{count} {what}")]
+    Violations { count: usize, what: &'static str },
+}
+
+/// Returns the given error as a schema one, keeping it as an IO error if it is one.
+pub fn schema_error(err: anyhow::Error) -> alvidir::schema::Error {
+    match err.downcast::<io::Error>() {
+        Ok(err) => err.into(),
+        Err(err) => alvidir::schema::Error::custom(err),
+    }
+}
+
+/// The class of an error, determining the exit code of the command.
+///
+/// Codes are stable, so scripts can rely on them. Usage errors exit with code 2, as reported by
+/// the argument parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Any error not in the classes below.
+    Other,
+    /// The requested document, backup or template does not exist.
+    NotFound,
+    /// The document or backup already exists, or the id is ambiguous.
+    Conflict,
+    /// Some constraint of the schema is not satisfied.
+    Constraint,
+    /// The command would modify documents in read-only mode.
+    ReadOnly,
+    /// Reading or writing a file failed.
+    Io,
+}
+
+impl ErrorKind {
+    /// Returns the class of the given error, as the first known one in its chain of causes.
+    pub fn of(err: &anyhow::Error) -> Self {
+        err.chain()
+            .find_map(|cause| {
+                if let Some(err) = cause.downcast_ref::<CliError>() {
+                    return Some(match err {
+                        CliError::DocumentNotFound(_)
+                        | CliError::BackupNotFound(_)
+                        | CliError::TemplateNotFound(_)
+                        | CliError::InexactId { .. } => ErrorKind::NotFound,
+                        CliError::DocumentExists(_)
+                        | CliError::BackupExists(_)
+                        | CliError::AmbiguousId { .. } => ErrorKind::Conflict,
+                        CliError::Violations { .. } => ErrorKind::Constraint,
+                    });
+                }
+
+                if let Some(err) = cause.downcast_ref::<alvidir::schema::Error>() {
+                    return match err {
+                        alvidir::schema::Error::ReadOnly => Some(ErrorKind::ReadOnly),
+                        alvidir::schema::Error::Io(_) => Some(ErrorKind::Io),
+                        alvidir::schema::Error::Msg(_) => Some(ErrorKind::Constraint),
+                        alvidir::schema::Error::Noop => None,
+                    };
+                }
+
+                cause.downcast_ref::<io::Error>().map(|_| ErrorKind::Io)
+            })
+            .unwrap_or(ErrorKind::Other)
+    }
+
+    /// Returns the exit code of self.
+    pub fn code(self) -> u8 {
+        match self {
+            ErrorKind::Other => 1,
+            ErrorKind::NotFound => 3,
+            ErrorKind::Conflict => 4,
+            ErrorKind::Constraint => 5,
+            ErrorKind::ReadOnly => 6,
+            ErrorKind::Io => 7,
+        }
+    }
+}
+
+/// An error as printed in JSON.
+#[derive(Serialize)]
+struct ErrorRecord {
+    /// The message of the error, including its causes.
+    error: String,
+    /// The class of the error.
+    kind: ErrorKind,
+    /// The exit code of the command.
+    code: u8,
+}
+
+/// The format in which errors are printed into the standard error.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ErrorFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// A JSON object with the message, kind and exit code of the error.
+    Json,
+}
+
+impl ErrorFormat {
+    /// Prints the given error in self's format, returning the exit code it corresponds to.
+    pub fn report(self, err: &anyhow::Error) -> ExitCode {
+        let kind = ErrorKind::of(err);
+        let mut stderr = io::stderr();
+        let _ = match self {
+            ErrorFormat::Text => writeln!(stderr, "Error: {err:?}"),
+            ErrorFormat::Json => serde_json::to_string(&ErrorRecord {
+                error: format!("{err:#}"),
+                kind,
+                code: kind.code(),
+            })
+            .map_err(io::Error::from)
+            .and_then(|json| writeln!(stderr, "{json}")),
+        };
+
+        ExitCode::from(kind.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io, path::PathBuf};
+
+    use alvidir::document::DocumentStore;
+
+    use crate::{document::Document, repository::LocalDocumentRepository};
+
+    use super::{CliError, ErrorKind};
+
+    #[test]
+    fn error_kind_of() {
+        struct Test {
+            name: &'static str,
+            err: anyhow::Error,
+            kind: ErrorKind,
+            code: u8,
+        }
+
+        let context = tempfile::tempdir().unwrap();
+        fs::write(context.path().join("a.md"), "a").unwrap();
+        let document_repo = LocalDocumentRepository {
+            context: context.path().to_path_buf(),
+            extension: "md".to_string(),
+        };
+
+        // The parent directory of the document is a file, so it cannot be created.
+        let failed_write = document_repo
+            .save(&Document {
+                path: PathBuf::from("a.md/b"),
+                bytes: Vec::default(),
+            })
+            .unwrap_err();
+
+        vec![
+            Test {
+                name: "unknown error",
+                err: anyhow::Error::msg("unknown"),
+                kind: ErrorKind::Other,
+                code: 1,
+            },
+            Test {
+                name: "missing document",
+                err: CliError::DocumentNotFound(PathBuf::from("a")).into(),
+                kind: ErrorKind::NotFound,
+                code: 3,
+            },
+            Test {
+                name: "existing document",
+                err: CliError::DocumentExists(PathBuf::from("a")).into(),
+                kind: ErrorKind::Conflict,
+                code: 4,
+            },
+            Test {
+                name: "schema constraint",
+                err: alvidir::schema::Error::custom("invalid").into(),
+                kind: ErrorKind::Constraint,
+                code: 5,
+            },
+            Test {
+                name: "read-only schema",
+                err: alvidir::schema::Error::ReadOnly.into(),
+                kind: ErrorKind::ReadOnly,
+                code: 6,
+            },
+            Test {
+                name: "io error",
+                err: io::Error::other("broken").into(),
+                kind: ErrorKind::Io,
+                code: 7,
+            },
+            Test {
+                name: "failed write into the repository",
+                err: failed_write.into(),
+                kind: ErrorKind::Io,
+                code: 7,
+            },
+            Test {
+                name: "io error with context",
+                err: anyhow::Error::from(io::Error::other("broken")).context("saving a"),
+                kind: ErrorKind::Io,
+                code: 7,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let kind = ErrorKind::of(&test.err);
+            assert_eq!(kind, test.kind, "{}", test.name);
+            assert_eq!(kind.code(), test.code, "{}", test.name);
+        });
+    }
+}
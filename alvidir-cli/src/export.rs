@@ -0,0 +1,301 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use alvidir::{
+    deref::TryDeref,
+    document::{lazy::LazyDocument, DocumentRepository, DocumentStore},
+    id::Identify,
+    schema::Schema,
+};
+use anyhow::Result;
+use clap::Args;
+
+use crate::{
+    checksum::{Checksums, CHECKSUMS_FILE},
+    document::Document,
+    dryrun::WriteMode,
+    error::CliError,
+    progress::Progress,
+    repository::LocalDocumentRepository,
+};
+
+/// Export documents into another directory.
+#[derive(Args)]
+pub struct ExportCommand {
+    /// The directory to export documents into.
+    target: PathBuf,
+    /// Export only the documents under the given path (e.g. 'sub'). May be repeated.
+    #[arg(long)]
+    only: Vec<PathBuf>,
+    /// Prepend the given path to the id of every exported document, updating the links between
+    /// them accordingly.
+    #[arg(long)]
+    prefix: Option<PathBuf>,
+}
+
+pub struct ExportCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    /// The file's extension.
+    pub extension: String,
+    /// Whether documents are written into the target directory.
+    pub mode: WriteMode,
+}
+
+impl<DocumentRepo> ExportCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    pub fn execute(&self, command: ExportCommand) -> Result<()> {
+        let remap = |id: &Path| match &command.prefix {
+            Some(prefix) => prefix.join(id),
+            None => id.to_path_buf(),
+        };
+
+        let documents: BTreeMap<PathBuf, Document> = self
+            .schema
+            .read()
+            .into_iter()
+            .filter(|document| {
+                command.only.is_empty()
+                    || command
+                        .only
+                        .iter()
+                        .any(|path| document.id().starts_with(path))
+            })
+            .filter_map(|document| document.try_deref().cloned())
+            .map(|document| (document.id().clone(), document))
+            .collect();
+
+        let target_repo = LocalDocumentRepository {
+            context: command.target.clone(),
+            extension: self.extension.clone(),
+        };
+
+        let exported: Vec<Document> = documents
+            .values()
+            .map(|document| Document {
+                path: remap(document.id()),
+                bytes: document
+                    .replace_links(|target| documents.contains_key(target).then(|| remap(target))),
+            })
+            .collect();
+
+        let existing: BTreeSet<PathBuf> = if command.target.exists() {
+            target_repo.ids().collect()
+        } else {
+            BTreeSet::default()
+        };
+
+        if let Some(document) = exported
+            .iter()
+            .find(|document| existing.contains(document.id()))
+        {
+            return Err(
+                anyhow::Error::new(CliError::DocumentExists(document.id().clone()))
+                    .context(format!("exporting into {}", command.target.display())),
+            );
+        }
+
+        if !self.mode.allows(format_args!(
+            "export {} documents into {}",
+            exported.len(),
+            command.target.display()
+        ))? {
+            return Ok(());
+        }
+
+        // Checksums are exported along with documents, so the target can be verified on its own.
+        let mut checksums = Checksums::load(command.target.join(CHECKSUMS_FILE))?;
+        let progress = Progress::new("exporting", exported.len());
+        for document in &exported {
+            target_repo.save(document)?;
+            checksums.insert(document);
+            progress.inc();
+        }
+
+        checksums.store()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::{
+        checksum::CHECKSUMS_FILE,
+        dryrun::WriteMode,
+        error::ErrorKind,
+        repository::{fixtures::local_schema, LocalDocumentRepository},
+    };
+
+    use super::{ExportCli, ExportCommand};
+
+    #[test]
+    fn export_documents() {
+        struct Test {
+            name: &'static str,
+            only: Vec<&'static str>,
+            prefix: Option<&'static str>,
+            /// The content of every exported document, by path relative to the target.
+            exported: Vec<(&'static str, &'static str)>,
+        }
+
+        vec![
+            Test {
+                name: "every document",
+                only: vec![],
+                prefix: None,
+                exported: vec![
+                    ("a.md", "see [[sub/b]] and [[c]]"),
+                    ("c.md", "c"),
+                    ("sub/b.md", "back to [[a]]"),
+                ],
+            },
+            Test {
+                name: "only documents under a path",
+                only: vec!["sub"],
+                prefix: None,
+                exported: vec![("sub/b.md", "back to [[a]]")],
+            },
+            Test {
+                name: "prefixed ids",
+                only: vec![],
+                prefix: Some("out"),
+                exported: vec![
+                    ("out/a.md", "see [[out/sub/b]] and [[out/c]]"),
+                    ("out/c.md", "c"),
+                    ("out/sub/b.md", "back to [[out/a]]"),
+                ],
+            },
+            Test {
+                name: "prefixed ids keep links to documents not exported",
+                only: vec!["sub"],
+                prefix: Some("out"),
+                exported: vec![("out/sub/b.md", "back to [[a]]")],
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let context = tempfile::tempdir().unwrap();
+            fs::create_dir_all(context.path().join("sub")).unwrap();
+            fs::write(context.path().join("a.md"), "see [[sub/b]] and [[c]]").unwrap();
+            fs::write(context.path().join("c.md"), "c").unwrap();
+            fs::write(context.path().join("sub/b.md"), "back to [[a]]").unwrap();
+
+            let (_, schema) = local_schema(context.path());
+            let cli = ExportCli {
+                schema,
+                extension: "md".to_string(),
+                mode: WriteMode::Write,
+            };
+
+            let target = tempfile::tempdir().unwrap();
+            cli.execute(ExportCommand {
+                target: target.path().to_path_buf(),
+                only: test.only.into_iter().map(PathBuf::from).collect(),
+                prefix: test.prefix.map(PathBuf::from),
+            })
+            .unwrap();
+
+            let target_repo = LocalDocumentRepository {
+                context: target.path().to_path_buf(),
+                extension: "md".to_string(),
+            };
+
+            let mut exported: Vec<_> = target_repo
+                .ids()
+                .map(|id| {
+                    let path = id.with_extension("md");
+                    let content = fs::read_to_string(target.path().join(&path)).unwrap();
+                    (path.display().to_string(), content)
+                })
+                .collect();
+
+            exported.sort();
+            let want: Vec<_> = test
+                .exported
+                .into_iter()
+                .map(|(path, content)| (path.to_string(), content.to_string()))
+                .collect();
+
+            assert_eq!(exported, want, "{}", test.name);
+        });
+    }
+
+    #[test]
+    fn export_respects_write_mode() {
+        struct Test {
+            name: &'static str,
+            mode: WriteMode,
+            read_only: bool,
+            exported: bool,
+        }
+
+        vec![
+            Test {
+                name: "write",
+                mode: WriteMode::Write,
+                read_only: false,
+                exported: true,
+            },
+            Test {
+                name: "read only",
+                mode: WriteMode::ReadOnly,
+                read_only: true,
+                exported: false,
+            },
+            Test {
+                name: "dry run",
+                mode: WriteMode::DryRun,
+                read_only: false,
+                exported: false,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let context = tempfile::tempdir().unwrap();
+            fs::write(context.path().join("a.md"), "a").unwrap();
+
+            let (_, schema) = local_schema(context.path());
+            let cli = ExportCli {
+                schema,
+                extension: "md".to_string(),
+                mode: test.mode,
+            };
+
+            let target = tempfile::tempdir().unwrap();
+            match cli.execute(ExportCommand {
+                target: target.path().to_path_buf(),
+                only: Vec::default(),
+                prefix: None,
+            }) {
+                Ok(_) => assert!(!test.read_only, "{}: export must fail", test.name),
+                Err(err) => assert!(
+                    test.read_only && ErrorKind::of(&err) == ErrorKind::ReadOnly,
+                    "{}: unexpected error {err}",
+                    test.name
+                ),
+            }
+
+            assert_eq!(
+                target.path().join("a.md").exists(),
+                test.exported,
+                "{}",
+                test.name
+            );
+
+            assert_eq!(
+                target.path().join(CHECKSUMS_FILE).exists(),
+                test.exported,
+                "{}",
+                test.name
+            );
+        });
+    }
+}
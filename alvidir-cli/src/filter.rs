@@ -0,0 +1,538 @@
+use std::{
+    iter::Peekable,
+    path::{Path, PathBuf},
+    str::CharIndices,
+};
+
+use alvidir::{deref::TryDeref, id::Identify};
+use alvidir_plugin_interval::Interval;
+use anyhow::{Error, Result};
+use regex::Regex;
+
+use crate::document::{Document, Period};
+
+/// The keyword joining two conditions that must hold at once.
+const AND: &str = "AND";
+
+/// The keyword joining two conditions of which, at least, one must hold.
+const OR: &str = "OR";
+
+/// A field of a document a condition applies to.
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    /// The id of the document.
+    Id,
+    /// The file name of the document.
+    Name,
+    /// The content of the document.
+    Content,
+    /// The id of any document the document links to.
+    Link,
+    /// Any period of time the document refers to.
+    Period,
+}
+
+impl TryFrom<&str> for Field {
+    type Error = Error;
+
+    fn try_from(field: &str) -> Result<Self> {
+        match field {
+            "id" => Ok(Self::Id),
+            "name" => Ok(Self::Name),
+            "content" => Ok(Self::Content),
+            "link" => Ok(Self::Link),
+            "period" => Ok(Self::Period),
+            other => Err(Error::msg(format!(
+                "unknown field {other:?}, expected one of id, name, content, link or period"
+            ))),
+        }
+    }
+}
+
+/// The comparison between a field and a value.
+enum Operator {
+    /// The field must be equal to the value.
+    Equal,
+    /// The field must not be equal to the value.
+    NotEqual,
+    /// The field must contain the value.
+    Contains,
+    /// The field must match the value, as a regular expression.
+    Matches(Regex),
+    /// The field, a period, must intersect the value.
+    Intersects(Period),
+}
+
+impl Operator {
+    fn holds(&self, field: &str, value: &str) -> bool {
+        match self {
+            Self::Equal => field == value,
+            Self::NotEqual => field != value,
+            Self::Contains => field.contains(value),
+            Self::Matches(regex) => regex.is_match(field),
+            Self::Intersects(period) => field
+                .parse::<Period>()
+                .is_ok_and(|field| field.lo() <= period.hi() && period.lo() <= field.hi()),
+        }
+    }
+}
+
+/// A single comparison in a [`DocumentFilter`].
+struct Condition {
+    field: Field,
+    operator: Operator,
+    value: String,
+}
+
+impl Condition {
+    fn holds(&self, id: &str, document: Option<&Document>) -> bool {
+        match self.field {
+            Field::Id => self.operator.holds(id, &self.value),
+            Field::Name => {
+                let name = Path::new(id)
+                    .file_name()
+                    .map(|name| name.to_string_lossy())
+                    .unwrap_or_default();
+
+                self.operator.holds(&name, &self.value)
+            }
+            Field::Content => document.is_some_and(|document| {
+                self.operator
+                    .holds(&String::from_utf8_lossy(&document.bytes), &self.value)
+            }),
+            Field::Link => {
+                let mut links = document
+                    .into_iter()
+                    .flat_map(Document::links)
+                    .map(|link| link.display().to_string());
+
+                match &self.operator {
+                    // A document not linking to the value is the one none of its links is equal to.
+                    Operator::NotEqual => links.all(|link| link != self.value),
+                    operator => links.any(|link| operator.holds(&link, &self.value)),
+                }
+            }
+            Field::Period => {
+                let mut periods = document
+                    .into_iter()
+                    .flat_map(Document::periods)
+                    .map(|period| period.to_string());
+
+                match &self.operator {
+                    Operator::NotEqual => periods.all(|period| period != self.value),
+                    operator => periods.any(|period| operator.holds(&period, &self.value)),
+                }
+            }
+        }
+    }
+}
+
+/// A user-defined expression documents must match (e.g. `id~"sub/" AND link=a`).
+///
+/// Each condition is made of a field, an operator and a value, quoted if it has whitespaces.
+/// The available fields are:
+/// - `id`: the id of the document.
+/// - `name`: the file name of the document.
+/// - `content`: the content of the document, only loaded if the filter uses it.
+/// - `link`: the id of any document the document links to.
+/// - `period`: any period of time the document refers to (e.g. `@(circa 1800 ± 5y)`).
+///
+/// The available operators are `=`, `!=`, `~` (contains) and `=~` (matches a regular
+/// expression). On periods, `~` holds if the period intersects the value, a year or range of
+/// years (e.g. `period~1805..1810`). Conditions are joined by `AND` and
+/// `OR`, the former taking precedence over the latter.
+pub struct DocumentFilter {
+    /// The disjunction of conjunctions of conditions.
+    any: Vec<Vec<Condition>>,
+    /// Whether the filter requires the content of the document.
+    loads_content: bool,
+}
+
+impl DocumentFilter {
+    /// Parses the given source into a [`DocumentFilter`].
+    pub fn new(source: impl AsRef<str>) -> Result<Self> {
+        let mut parser = Parser::new(source.as_ref());
+        let mut any = vec![vec![parser.condition()?]];
+
+        while let Some(keyword) = parser.keyword()? {
+            let condition = parser.condition()?;
+            match keyword {
+                AND => any
+                    .last_mut()
+                    .expect("disjunction should never be empty")
+                    .push(condition),
+                _ => any.push(vec![condition]),
+            }
+        }
+
+        let loads_content = any
+            .iter()
+            .flatten()
+            .any(|condition| !matches!(condition.field, Field::Id | Field::Name));
+
+        Ok(Self { any, loads_content })
+    }
+
+    /// Returns true if, and only if, the given document matches the filter.
+    pub fn matches<D>(&self, document: &D) -> bool
+    where
+        D: Identify<Id = PathBuf> + TryDeref<Target = Document>,
+    {
+        let id = document.id().display().to_string();
+        let content = self.loads_content.then(|| document.try_deref()).flatten();
+
+        self.any
+            .iter()
+            .any(|all| all.iter().all(|condition| condition.holds(&id, content)))
+    }
+}
+
+/// A hand-written parser for the [`DocumentFilter`] syntax.
+struct Parser<'a> {
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            chars: source.char_indices().peekable(),
+        }
+    }
+
+    /// Parses a condition, like `field=value`.
+    fn condition(&mut self) -> Result<Condition> {
+        self.skip_whitespaces();
+        let field = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+        let field = Field::try_from(field)?;
+
+        self.skip_whitespaces();
+        let symbol = match self.chars.next() {
+            Some((_, '=')) if self.chars.next_if(|&(_, c)| c == '~').is_some() => "=~",
+            Some((_, '=')) => "=",
+            Some((_, '~')) => "~",
+            Some((_, '!')) if self.chars.next_if(|&(_, c)| c == '=').is_some() => "!=",
+            _ => return Err(Error::msg("expected an operator: =, !=, ~ or =~")),
+        };
+
+        self.skip_whitespaces();
+        let value = self.value()?;
+        if value.is_empty() {
+            return Err(Error::msg("expected a value after the operator"));
+        }
+
+        let operator = match symbol {
+            "=" => Operator::Equal,
+            "!=" => Operator::NotEqual,
+            "~" if field == Field::Period => Operator::Intersects(
+                value
+                    .parse()
+                    .map_err(|err| Error::msg(format!("invalid period: {err}")))?,
+            ),
+            "~" => Operator::Contains,
+            _ => Operator::Matches(
+                Regex::new(&value)
+                    .map_err(|err| Error::msg(format!("invalid regular expression: {err}")))?,
+            ),
+        };
+
+        Ok(Condition {
+            field,
+            operator,
+            value,
+        })
+    }
+
+    /// Parses a value, either quoted or up to the next whitespace.
+    fn value(&mut self) -> Result<String> {
+        if self.chars.next_if(|&(_, c)| c == '"').is_none() {
+            return Ok(self.take_while(|c| !c.is_whitespace()).to_string());
+        }
+
+        let mut value = String::new();
+        while let Some((_, c)) = self.chars.next() {
+            match c {
+                '"' => return Ok(value),
+                '\\' => value.extend(self.chars.next().map(|(_, c)| c)),
+                c => value.push(c),
+            }
+        }
+
+        Err(Error::msg("unterminated quoted value"))
+    }
+
+    /// Parses the keyword joining two conditions, if any.
+    fn keyword(&mut self) -> Result<Option<&'static str>> {
+        self.skip_whitespaces();
+        if self.chars.peek().is_none() {
+            return Ok(None);
+        }
+
+        match self.take_while(|c| !c.is_whitespace()) {
+            keyword if keyword.eq_ignore_ascii_case(AND) => Ok(Some(AND)),
+            keyword if keyword.eq_ignore_ascii_case(OR) => Ok(Some(OR)),
+            other => Err(Error::msg(format!("expected AND or OR, found {other:?}"))),
+        }
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let start = self
+            .chars
+            .peek()
+            .map(|&(index, _)| index)
+            .unwrap_or(self.source.len());
+
+        while self.chars.next_if(|&(_, c)| f(c)).is_some() {}
+
+        let end = self
+            .chars
+            .peek()
+            .map(|&(index, _)| index)
+            .unwrap_or(self.source.len());
+
+        &self.source[start..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use alvidir::document::{lazy::LazyDocument, memory::MemoryDocumentRepository};
+
+    use crate::document::Document;
+
+    use super::DocumentFilter;
+
+    #[test]
+    fn filter_documents() {
+        struct Test {
+            name: &'static str,
+            filter: &'static str,
+            id: &'static str,
+            content: &'static str,
+            /// Whether the document matches, or none if the filter is malformed.
+            want: Option<bool>,
+        }
+
+        vec![
+            Test {
+                name: "equal id",
+                filter: "id=sub/b",
+                id: "sub/b",
+                content: "",
+                want: Some(true),
+            },
+            Test {
+                name: "different id",
+                filter: "id=sub/c",
+                id: "sub/b",
+                content: "",
+                want: Some(false),
+            },
+            Test {
+                name: "not equal name",
+                filter: "name!=c",
+                id: "sub/b",
+                content: "",
+                want: Some(true),
+            },
+            Test {
+                name: "id containing value",
+                filter: "id~sub/",
+                id: "sub/b",
+                content: "",
+                want: Some(true),
+            },
+            Test {
+                name: "content matching regular expression",
+                filter: "content=~^h.llo",
+                id: "a",
+                content: "hello world",
+                want: Some(true),
+            },
+            Test {
+                name: "link equal to value",
+                filter: "link=sub/b",
+                id: "a",
+                content: "see [[sub/b]]",
+                want: Some(true),
+            },
+            Test {
+                name: "no link equal to value",
+                filter: "link!=sub/b",
+                id: "a",
+                content: "see [[sub/b]] and [[c]]",
+                want: Some(false),
+            },
+            Test {
+                name: "period equal to value",
+                filter: "period=1800..1810",
+                id: "a",
+                content: "from @(1800..1810)",
+                want: Some(true),
+            },
+            Test {
+                name: "period intersecting value",
+                filter: "period~1805",
+                id: "a",
+                content: "from @(1800..1810)",
+                want: Some(true),
+            },
+            Test {
+                name: "period not intersecting value",
+                filter: "period~1811..1820",
+                id: "a",
+                content: "from @(1800..1810)",
+                want: Some(false),
+            },
+            Test {
+                name: "period possibly intersecting value",
+                filter: "period~1812",
+                id: "a",
+                content: "from @(circa 1800..1810 ± 2y)",
+                want: Some(true),
+            },
+            Test {
+                name: "malformed period in content",
+                filter: "period~1805",
+                id: "a",
+                content: "from @(the 19th century)",
+                want: Some(false),
+            },
+            Test {
+                name: "malformed period in value",
+                filter: "period~19th",
+                id: "a",
+                content: "",
+                want: None,
+            },
+            Test {
+                name: "quoted value with whitespaces",
+                filter: r#"content~"hello world""#,
+                id: "a",
+                content: "say hello world",
+                want: Some(true),
+            },
+            Test {
+                name: "quoted value with escaped quote",
+                filter: r#"content~"say \"hi\"""#,
+                id: "a",
+                content: r#"say "hi""#,
+                want: Some(true),
+            },
+            Test {
+                name: "whitespaces around operator",
+                filter: "id = a",
+                id: "a",
+                content: "",
+                want: Some(true),
+            },
+            Test {
+                name: "and of conditions",
+                filter: "id~sub AND name=b",
+                id: "sub/b",
+                content: "",
+                want: Some(true),
+            },
+            Test {
+                name: "and with a failing condition",
+                filter: "id~sub and name=c",
+                id: "sub/b",
+                content: "",
+                want: Some(false),
+            },
+            Test {
+                name: "or of conditions",
+                filter: "id=c OR name=b",
+                id: "sub/b",
+                content: "",
+                want: Some(true),
+            },
+            Test {
+                name: "and takes precedence over or",
+                filter: "id=a OR id=c AND name=b",
+                id: "a",
+                content: "",
+                want: Some(true),
+            },
+            Test {
+                name: "and takes precedence over a following or",
+                filter: "id=c AND name=x OR name=b",
+                id: "sub/b",
+                content: "",
+                want: Some(true),
+            },
+            Test {
+                name: "unknown field",
+                filter: "title=a",
+                id: "a",
+                content: "",
+                want: None,
+            },
+            Test {
+                name: "missing operator",
+                filter: "id a",
+                id: "a",
+                content: "",
+                want: None,
+            },
+            Test {
+                name: "missing value",
+                filter: "id=",
+                id: "a",
+                content: "",
+                want: None,
+            },
+            Test {
+                name: "unterminated quoted value",
+                filter: r#"content~"hello"#,
+                id: "a",
+                content: "",
+                want: None,
+            },
+            Test {
+                name: "invalid regular expression",
+                filter: "id=~(",
+                id: "a",
+                content: "",
+                want: None,
+            },
+            Test {
+                name: "unknown keyword",
+                filter: "id=a XOR id=b",
+                id: "a",
+                content: "",
+                want: None,
+            },
+            Test {
+                name: "dangling keyword",
+                filter: "id=a AND",
+                id: "a",
+                content: "",
+                want: None,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let document = LazyDocument::new(
+                Arc::new(MemoryDocumentRepository::default()),
+                Document {
+                    path: PathBuf::from(test.id),
+                    bytes: test.content.as_bytes().to_vec(),
+                },
+            );
+
+            let got = DocumentFilter::new(test.filter)
+                .ok()
+                .map(|filter| filter.matches(&document));
+
+            assert_eq!(got, test.want, "{}", test.name);
+        });
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    path::Path,
+    sync::Arc,
+};
+
+use alvidir::{
+    deref::TryDeref,
+    document::{lazy::LazyDocument, DocumentRepository},
+    id::Identify,
+    schema::Schema,
+};
+use anyhow::Result;
+use clap::Args;
+
+use crate::{document::Document, pager};
+
+/// Print the links between documents as a Graphviz DOT graph.
+#[derive(Args)]
+pub struct GraphCommand;
+
+pub struct GraphCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+}
+
+impl<DocumentRepo> GraphCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    pub fn execute(&self, _: GraphCommand) -> Result<()> {
+        let graph = self.schema.read();
+        let ids: BTreeSet<_> = graph.into_iter().map(|document| document.id()).collect();
+
+        // Links to missing documents are left out, as reported by the check command.
+        let mut edges = BTreeMap::<_, usize>::new();
+        for document in graph
+            .into_iter()
+            .filter_map(|document| document.try_deref())
+        {
+            for link in document.links().filter(|link| ids.contains(link)) {
+                *edges.entry((document.id(), link)).or_default() += 1;
+            }
+        }
+
+        let mut stdout = pager::stdout();
+        writeln!(stdout, "digraph alvidir {{")?;
+        for id in &ids {
+            writeln!(stdout, "  {};", quote(id))?;
+        }
+
+        for ((from, to), weight) in edges {
+            writeln!(
+                stdout,
+                "  {} -> {} [weight={weight}, label={weight}];",
+                quote(from),
+                quote(&to)
+            )?;
+        }
+
+        writeln!(stdout, "}}")?;
+        Ok(())
+    }
+}
+
+/// Returns the given id as a quoted DOT identifier.
+fn quote(id: &Path) -> String {
+    format!("\"{}\"", id.display().to_string().replace('"', "\\\""))
+}
@@ -0,0 +1,200 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use alvidir::{
+    document::{lazy::LazyDocument, DocumentRepository, DocumentStore},
+    graph::Source,
+    schema::{ops::save::Save, Schema},
+};
+use anyhow::Result;
+use clap::Args;
+
+use crate::{document::Document, error::CliError, logging, progress::Progress};
+
+/// Create one document per row of a CSV file.
+#[derive(Args)]
+pub struct ImportCommand {
+    /// The CSV file to import documents from, with a header row.
+    file: PathBuf,
+    /// The column holding the id of each document.
+    #[arg(long, default_value = "id")]
+    id_column: String,
+    /// The column holding the content of each document.
+    #[arg(long, default_value = "content")]
+    content_column: String,
+}
+
+pub struct ImportCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub document_repo: Arc<DocumentRepo>,
+}
+
+impl<DocumentRepo> ImportCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document> + DocumentStore,
+{
+    pub fn execute(&self, command: ImportCommand) -> Result<()> {
+        let mut reader = csv::Reader::from_path(&command.file)?;
+        let headers = reader.headers()?.clone();
+        let column = |name: &str| {
+            headers
+                .iter()
+                .position(|header| header == name)
+                .ok_or_else(|| anyhow::Error::msg(format!("missing column {name:?}")))
+        };
+
+        let id_column = column(&command.id_column)?;
+        let content_column = column(&command.content_column)?;
+
+        // Rows are imported one by one, so a failing row does not abort the whole import.
+        let mut rows = 0;
+        let mut failed = 0;
+        let progress = Progress::spinner("importing");
+        for record in reader.records() {
+            rows += 1;
+            progress.inc();
+            let result = record.map_err(anyhow::Error::from).and_then(|record| {
+                self.import(Document {
+                    path: record.get(id_column).unwrap_or_default().trim().into(),
+                    bytes: record.get(content_column).unwrap_or_default().into(),
+                })
+            });
+
+            if let Err(err) = result {
+                failed += 1;
+                progress.suspend(|| writeln!(io::stderr(), "row {rows}: {err}"))?;
+            }
+        }
+
+        if failed > 0 {
+            return Err(anyhow::Error::msg(format!(
+                "{failed} of {rows} rows failed to import"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Saves the given document, failing if another one with the same id already exists.
+    fn import(&self, document: Document) -> Result<()> {
+        if document.path.as_os_str().is_empty() {
+            return Err(anyhow::Error::msg("document id must be set"));
+        }
+
+        if self.schema.read().contains(&document.path) {
+            return Err(CliError::DocumentExists(document.path.clone()).into());
+        }
+
+        Save::new(LazyDocument::new(
+            self.document_repo.clone(),
+            document.clone(),
+        ))
+        .execute(self.schema.transaction())?;
+
+        self.document_repo.save(&document)?;
+        logging::audit("save", document.path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, fs};
+
+    use clap::Parser;
+
+    use crate::repository::fixtures::local_schema;
+
+    use super::{ImportCli, ImportCommand};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        command: ImportCommand,
+    }
+
+    #[test]
+    fn import_documents() {
+        struct Test {
+            name: &'static str,
+            csv: &'static str,
+            args: Vec<&'static str>,
+            ok: bool,
+            want: Vec<(&'static str, &'static str)>,
+        }
+
+        vec![
+            Test {
+                name: "one document per row",
+                csv: "id,content\nb,b\nsub/c,\"multi\nline, [[b]]\"\n",
+                args: vec![],
+                ok: true,
+                want: vec![("a", "a"), ("b", "b"), ("sub/c", "multi\nline, [[b]]")],
+            },
+            Test {
+                name: "custom columns",
+                csv: "name,body,other\nb,b,ignored\n",
+                args: vec!["--id-column", "name", "--content-column", "body"],
+                ok: true,
+                want: vec![("a", "a"), ("b", "b")],
+            },
+            Test {
+                name: "missing column",
+                csv: "name,content\nb,b\n",
+                args: vec![],
+                ok: false,
+                want: vec![("a", "a")],
+            },
+            Test {
+                name: "failing rows do not abort the import",
+                csv: "id,content\n  ,empty\na,overwritten\nb,b\nb,duplicated\n",
+                args: vec![],
+                ok: false,
+                want: vec![("a", "a"), ("b", "b")],
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let context = tempfile::tempdir().unwrap();
+            fs::write(context.path().join("a.md"), "a").unwrap();
+
+            let file = context.path().join("import.csv");
+            fs::write(&file, test.csv).unwrap();
+
+            let (document_repo, schema) = local_schema(context.path());
+            let import_cli = ImportCli {
+                schema,
+                document_repo: document_repo.clone(),
+            };
+
+            let args = ["import", file.to_str().unwrap()]
+                .into_iter()
+                .chain(test.args.iter().copied());
+
+            let result = import_cli.execute(Cli::parse_from(args).command);
+            assert_eq!(result.is_ok(), test.ok, "{}: {result:?}", test.name);
+
+            let got: BTreeMap<_, _> = document_repo
+                .ids()
+                .map(|id| {
+                    let path = context.path().join(&id).with_extension("md");
+                    (id.display().to_string(), fs::read_to_string(path).unwrap())
+                })
+                .collect();
+
+            let want: BTreeMap<_, _> = test
+                .want
+                .iter()
+                .map(|(id, content)| (id.to_string(), content.to_string()))
+                .collect();
+
+            assert_eq!(got, want, "{}", test.name);
+        });
+    }
+}
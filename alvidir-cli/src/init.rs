@@ -0,0 +1,109 @@
+use std::{path::PathBuf, sync::Arc};
+
+use alvidir::document::DocumentStore;
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+
+use crate::{document::Document, repository::LocalDocumentRepository};
+
+/// The starter documents of a novel, as pairs of id and content.
+const NOVEL: &[(&str, &str)] = &[
+    (
+        "index",
+        "# Novel\n\n- [[synopsis]]\n- [[characters/protagonist]]\n- [[chapters/01]]\n",
+    ),
+    ("synopsis", "# Synopsis\n\nWhat is the story about?\n"),
+    (
+        "characters/protagonist",
+        "# Protagonist\n\nWho are they, and what do they want?\n",
+    ),
+    (
+        "chapters/01",
+        "# Chapter 1\n\nFeaturing [[characters/protagonist]].\n",
+    ),
+];
+
+/// The starter documents of a tabletop role-playing campaign, as pairs of id and content.
+const TTRPG: &[(&str, &str)] = &[
+    (
+        "index",
+        "# Campaign\n\n- [[setting]]\n- [[players]]\n- [[sessions/01]]\n",
+    ),
+    (
+        "setting",
+        "# Setting\n\nWhere does the campaign take place?\n",
+    ),
+    ("players", "# Players\n\nWho plays which character?\n"),
+    (
+        "sessions/01",
+        "# Session 1\n\nSet in [[setting]].\n\n## Notes\n",
+    ),
+];
+
+/// The starter documents of a historical research, as pairs of id and content.
+const HISTORY: &[(&str, &str)] = &[
+    (
+        "index",
+        "# History\n\n- [[timeline]]\n- [[people]]\n- [[sources]]\n",
+    ),
+    (
+        "timeline",
+        "# Timeline\n\nKey events, in chronological order.\n",
+    ),
+    ("people", "# People\n\nWho took part, and when.\n"),
+    ("sources", "# Sources\n\nWhere does each fact come from?\n"),
+];
+
+/// The kind of project to scaffold.
+#[derive(Clone, Copy, ValueEnum)]
+enum ProjectTemplate {
+    /// Synopsis, characters and chapters.
+    Novel,
+    /// Setting, players and sessions.
+    Ttrpg,
+    /// Timeline, people and sources.
+    History,
+}
+
+impl ProjectTemplate {
+    /// Returns the starter documents of the template, as pairs of id and content.
+    fn documents(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            ProjectTemplate::Novel => NOVEL,
+            ProjectTemplate::Ttrpg => TTRPG,
+            ProjectTemplate::History => HISTORY,
+        }
+    }
+}
+
+/// Scaffold a new project in the context.
+#[derive(Args)]
+pub struct InitCommand {
+    /// The kind of project to scaffold.
+    #[arg(long, value_enum)]
+    template: ProjectTemplate,
+}
+
+pub struct InitCli {
+    pub document_repo: Arc<LocalDocumentRepository>,
+}
+
+impl InitCli {
+    pub fn execute(&self, command: InitCommand) -> Result<()> {
+        if self.document_repo.context.exists() && self.document_repo.ids().next().is_some() {
+            return Err(anyhow::Error::msg(format!(
+                "{} already contains documents",
+                self.document_repo.context.display()
+            )));
+        }
+
+        for (id, content) in command.template.documents() {
+            self.document_repo.save(&Document {
+                path: PathBuf::from(id),
+                bytes: content.as_bytes().to_vec(),
+            })?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,322 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use alvidir::{
+    clock::{Clock, SystemClock},
+    document::{DocumentArchive, DocumentRepository, DocumentStore, DocumentTrash},
+    id::Identify,
+    schema::Result,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{document::Document, error::schema_error, repository::LocalDocumentRepository};
+
+/// The file, relative to the context, in which the journal of changes is kept.
+pub const JOURNAL_FILE: &str = ".alvidir/journal.jsonl";
+
+/// The maximum amount of entries kept in the journal, removing the oldest ones.
+const JOURNAL_SIZE: usize = 100;
+
+/// A single change into the documents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Change {
+    /// A document has been saved, overwriting the previous content, if any.
+    Save {
+        id: PathBuf,
+        #[serde(with = "base64_bytes")]
+        previous: Option<Vec<u8>>,
+    },
+    /// A document has been moved into the trash.
+    Trash { id: PathBuf },
+    /// A document has been moved out of the trash.
+    Restore { id: PathBuf },
+    /// A document has been archived.
+    Archive { id: PathBuf },
+    /// A document has been unarchived.
+    Unarchive { id: PathBuf },
+}
+
+/// Serializes optional bytes as a base64 string, so any content survives the JSON encoding.
+mod base64_bytes {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(bytes: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match bytes {
+            Some(bytes) => serializer.serialize_some(&STANDARD.encode(bytes)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .map(|encoded| STANDARD.decode(encoded).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+/// The changes performed by a single command.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    /// The moment the command finished at, in RFC 3339 format.
+    pub at: String,
+    /// The command line arguments.
+    pub command: String,
+    /// The changes, in the order they were performed.
+    pub changes: Vec<Change>,
+}
+
+/// Records the changes into the documents, allowing them to be undone.
+pub struct Journal {
+    /// The file in which entries are kept, one JSON document per line.
+    path: PathBuf,
+    /// The changes recorded since the last commit.
+    changes: Mutex<Vec<Change>>,
+    /// The clock telling the moment entries are committed at.
+    clock: Arc<dyn Clock>,
+}
+
+impl Journal {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            changes: Default::default(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Sets the clock telling the moment entries are committed at.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Records the given change as part of the current entry.
+    fn record(&self, change: Change) {
+        match self.changes.lock() {
+            Ok(mut changes) => changes.push(change),
+            Err(err) => tracing::error!(error = err.to_string(), "recording change"),
+        }
+    }
+
+    /// Forgets all the changes recorded since the last commit.
+    pub fn discard(&self) {
+        match self.changes.lock() {
+            Ok(mut changes) => changes.clear(),
+            Err(err) => tracing::error!(error = err.to_string(), "discarding changes"),
+        }
+    }
+
+    /// Appends all the changes recorded since the last commit as a single entry, if any.
+    pub fn commit(&self, command: &[String]) -> anyhow::Result<()> {
+        let changes = match self.changes.lock() {
+            Ok(mut changes) => std::mem::take(&mut *changes),
+            Err(err) => return Err(anyhow::Error::msg(err.to_string())),
+        };
+
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let mut entries = self.entries()?;
+        entries.push(Entry {
+            at: humantime::format_rfc3339_seconds(self.clock.now()).to_string(),
+            command: command.join(" "),
+            changes,
+        });
+
+        let start = entries.len().saturating_sub(JOURNAL_SIZE);
+        self.write(&entries[start..])
+    }
+
+    /// Returns all the entries in the journal, from the oldest to the newest.
+    pub fn entries(&self) -> anyhow::Result<Vec<Entry>> {
+        if !self.path.exists() {
+            return Ok(Vec::default());
+        }
+
+        fs::read_to_string(&self.path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(Into::into))
+            .collect()
+    }
+
+    /// Removes the given amount of entries from the end of the journal.
+    pub fn truncate(&self, n: usize) -> anyhow::Result<()> {
+        let mut entries = self.entries()?;
+        entries.truncate(entries.len().saturating_sub(n));
+        self.write(&entries)
+    }
+
+    /// Removes the given amount of changes from the end of the last entry in the journal.
+    pub fn truncate_last(&self, n: usize) -> anyhow::Result<()> {
+        let mut entries = self.entries()?;
+        if let Some(entry) = entries.last_mut() {
+            entry
+                .changes
+                .truncate(entry.changes.len().saturating_sub(n));
+        }
+
+        self.write(&entries)
+    }
+
+    /// Overwrites the journal with the given entries.
+    fn write(&self, entries: &[Entry]) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+
+        for entry in entries {
+            writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`LocalDocumentRepository`] decorator that records every change into a [`Journal`].
+pub struct JournaledRepository {
+    /// The repository being decorated.
+    document_repo: LocalDocumentRepository,
+    journal: Arc<Journal>,
+}
+
+impl JournaledRepository {
+    pub fn new(document_repo: LocalDocumentRepository, journal: Arc<Journal>) -> Self {
+        Self {
+            document_repo,
+            journal,
+        }
+    }
+}
+
+impl DocumentRepository for JournaledRepository {
+    type Document = Document;
+
+    fn find_by_id(&self, id: &<Self::Document as Identify>::Id) -> Option<Self::Document> {
+        self.document_repo.find_by_id(id)
+    }
+}
+
+impl DocumentStore for JournaledRepository {
+    fn save(&self, document: &Self::Document) -> Result<()> {
+        let previous = self
+            .document_repo
+            .contains(document.id())
+            .then(|| self.document_repo.find_by_id(document.id()))
+            .flatten()
+            .map(|previous| previous.bytes);
+
+        self.document_repo.save(document)?;
+        self.journal.record(Change::Save {
+            id: document.id().clone(),
+            previous,
+        });
+
+        Ok(())
+    }
+}
+
+impl DocumentTrash for JournaledRepository {
+    fn trash(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        self.document_repo.trash(id)?;
+        self.journal.record(Change::Trash { id: id.clone() });
+        Ok(())
+    }
+
+    fn restore(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        self.document_repo.restore(id)?;
+        self.journal.record(Change::Restore { id: id.clone() });
+        Ok(())
+    }
+
+    fn purge(&self) -> Result<()> {
+        self.document_repo.purge()?;
+        // Purged documents cannot be restored, so no entry can be undone anymore.
+        self.journal.discard();
+        self.journal.truncate(usize::MAX).map_err(schema_error)
+    }
+
+    fn trashed(&self) -> Vec<<Self::Document as Identify>::Id> {
+        self.document_repo.trashed()
+    }
+}
+
+impl DocumentArchive for JournaledRepository {
+    fn archive(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        self.document_repo.archive(id)?;
+        self.journal.record(Change::Archive { id: id.clone() });
+        Ok(())
+    }
+
+    fn unarchive(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        self.document_repo.unarchive(id)?;
+        self.journal.record(Change::Unarchive { id: id.clone() });
+        Ok(())
+    }
+
+    fn archived(&self) -> Vec<<Self::Document as Identify>::Id> {
+        self.document_repo.archived()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf, sync::Arc};
+
+    use alvidir::document::DocumentStore;
+
+    use crate::{document::Document, repository::LocalDocumentRepository};
+
+    use super::{Change, Journal, JournaledRepository, JOURNAL_FILE};
+
+    #[test]
+    fn previous_content_survives_the_journal() {
+        let context = tempfile::tempdir().unwrap();
+        // Not valid UTF-8, so it would be mangled if stored as text.
+        let previous = vec![0x66, 0x6f, 0xff, 0xfe, 0x00];
+        fs::write(context.path().join("a.md"), &previous).unwrap();
+
+        let journal = Arc::new(Journal::new(context.path().join(JOURNAL_FILE)));
+        let document_repo = JournaledRepository::new(
+            LocalDocumentRepository {
+                context: context.path().to_path_buf(),
+                extension: "md".to_string(),
+            },
+            journal.clone(),
+        );
+
+        document_repo
+            .save(&Document {
+                path: PathBuf::from("a"),
+                bytes: b"new".to_vec(),
+            })
+            .unwrap();
+
+        journal.commit(&["doc".to_string()]).unwrap();
+
+        let entries = journal.entries().unwrap();
+        assert!(matches!(
+            entries[0].changes.as_slice(),
+            [Change::Save { previous: Some(bytes), .. }] if *bytes == previous
+        ));
+    }
+}
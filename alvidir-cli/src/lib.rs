@@ -1,10 +1,83 @@
+use activity::ActivityCommand;
+use apply::ApplyCommand;
+use backup::BackupCommand;
+use check::CheckCommand;
+use checksum::VerifyCommand;
 use clap::Subcommand;
+use completions::{CompletionsCommand, ManCommand};
+use doctor::DoctorCommand;
 use document::DocumentCommand;
+use duplicates::DuplicatesCommand;
+use export::ExportCommand;
+use graph::GraphCommand;
+use import::ImportCommand;
+use init::InitCommand;
+use merge::MergeCommand;
+use search::SearchCommand;
+use shell::ShellCommand;
+use stats::StatsCommand;
+use template::TemplateCommand;
+use tui::TuiCommand;
+use undo::UndoCommand;
 
+pub mod activity;
+pub mod apply;
+pub mod backup;
+pub mod check;
+pub mod checksum;
+pub mod color;
+pub mod completions;
+pub mod config;
+pub mod constraints;
+pub mod doctor;
 pub mod document;
+pub mod dryrun;
+pub mod duplicates;
+pub mod error;
+pub mod export;
+pub mod filter;
+pub mod graph;
+pub mod import;
+pub mod init;
+pub mod journal;
+pub mod logging;
+pub mod merge;
+pub mod output;
+pub mod pager;
+pub mod policy;
+pub mod progress;
 pub mod repository;
+pub mod resolve;
+pub mod search;
+pub mod shell;
+pub mod similarity;
+pub mod stats;
+pub mod template;
+pub mod tui;
+pub mod undo;
+pub mod watch;
 
 #[derive(Subcommand)]
 pub enum CliCommand {
+    Activity(ActivityCommand),
+    Apply(ApplyCommand),
+    Backup(BackupCommand),
+    Check(CheckCommand),
+    Completions(CompletionsCommand),
     Doc(DocumentCommand),
+    Doctor(DoctorCommand),
+    Duplicates(DuplicatesCommand),
+    Export(ExportCommand),
+    Graph(GraphCommand),
+    Import(ImportCommand),
+    Init(InitCommand),
+    Man(ManCommand),
+    Merge(MergeCommand),
+    Search(SearchCommand),
+    Shell(ShellCommand),
+    Stats(StatsCommand),
+    Template(TemplateCommand),
+    Tui(TuiCommand),
+    Undo(UndoCommand),
+    Verify(VerifyCommand),
 }
@@ -0,0 +1,110 @@
+use std::{fmt::Display, fs, io, path::Path, time::Duration};
+
+use anyhow::Result;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::rolling::{Builder, Rotation};
+use tracing_subscriber::{
+    filter::filter_fn, fmt, layer::SubscriberExt, util::SubscriberInitExt, Layer,
+};
+
+/// The directory, relative to the context, in which log files are kept.
+pub const LOGS_DIR: &str = ".alvidir/logs";
+
+/// The prefix of all log files.
+pub const LOG_FILE_PREFIX: &str = "alvidir";
+
+/// The target of the events recording the executed commands.
+pub const COMMAND_TARGET: &str = "command";
+
+/// The target of the events recording the modified documents.
+pub const AUDIT_TARGET: &str = "audit";
+
+/// The maximum amount of log files to keep before removing the oldest ones.
+const MAX_LOG_FILES: usize = 7;
+
+/// Initializes the global logger, writing human-readable logs into the standard error and
+/// structured (JSON) logs into a daily-rotated file in the given context. Audit events are left out
+/// of the file unless `audit` is set.
+pub fn init(context: &Path, audit: bool) {
+    let stderr = fmt::layer()
+        .without_time()
+        .with_target(false)
+        .with_writer(io::stderr)
+        .with_filter(LevelFilter::INFO)
+        .with_filter(filter_fn(|metadata| {
+            metadata.target() != COMMAND_TARGET && metadata.target() != AUDIT_TARGET
+        }));
+
+    // A missing context has no documents to log about (e.g. before being initialized).
+    if !context.is_dir() {
+        tracing_subscriber::registry().with(stderr).init();
+        return;
+    }
+
+    let logs_dir = context.join(LOGS_DIR);
+    let appender = fs::create_dir_all(&logs_dir)
+        .map_err(anyhow::Error::new)
+        .and_then(|_| {
+            Builder::new()
+                .rotation(Rotation::DAILY)
+                .filename_prefix(LOG_FILE_PREFIX)
+                .filename_suffix("log")
+                .max_log_files(MAX_LOG_FILES)
+                .build(&logs_dir)
+                .map_err(anyhow::Error::new)
+        });
+
+    let registry = tracing_subscriber::registry().with(stderr);
+
+    match appender {
+        Ok(appender) => registry
+            .with(
+                fmt::layer()
+                    .json()
+                    .with_writer(appender)
+                    .with_filter(LevelFilter::INFO)
+                    .with_filter(filter_fn(move |metadata| {
+                        audit || metadata.target() != AUDIT_TARGET
+                    })),
+            )
+            .init(),
+        Err(err) => {
+            registry.init();
+            tracing::warn!(error = err.to_string(), "initializing log file");
+        }
+    }
+}
+
+/// Records the execution of a command with the given arguments.
+pub fn command<T>(args: &[String], elapsed: Duration, result: &Result<T>) {
+    let args = args.join(" ");
+    let duration_ms = elapsed.as_millis() as u64;
+
+    match result {
+        Ok(_) => tracing::info!(
+            target: COMMAND_TARGET,
+            args,
+            duration_ms,
+            outcome = "ok",
+            "command executed"
+        ),
+        Err(err) => tracing::info!(
+            target: COMMAND_TARGET,
+            args,
+            duration_ms,
+            outcome = "error",
+            error = err.to_string(),
+            "command executed"
+        ),
+    }
+}
+
+/// Records the given operation on the document with the given id.
+pub fn audit(operation: &str, document_id: impl Display) {
+    tracing::info!(
+        target: AUDIT_TARGET,
+        operation,
+        document_id = document_id.to_string(),
+        "document modified"
+    );
+}
@@ -1,18 +1,66 @@
 use std::{
     ffi::OsString,
-    io,
     path::PathBuf,
+    process::ExitCode,
     sync::{Arc, LazyLock},
+    time::{Duration, Instant},
 };
 
-use alvidir::{graph::Graph, schema::Schema};
-use alvidir_cli::{document::DocumentCli, repository::LocalDocumentRepository, CliCommand};
+use alvidir::{
+    clock::{Clock, SystemClock},
+    document::{
+        lazy::LazyDocument, readonly::ReadOnlyRepository, DocumentArchive, DocumentRepository,
+        DocumentStore, DocumentTrash,
+    },
+    graph::Graph,
+    schema::{middleware::DryRun, Schema},
+};
+use alvidir_cli::{
+    activity::ActivityCli,
+    apply::ApplyCli,
+    backup::{BackupCli, BACKUPS_DIR},
+    check::CheckCli,
+    checksum::{ChecksummedRepository, Checksums, VerifyCli, CHECKSUMS_FILE},
+    color,
+    completions::CompletionsCli,
+    config::Config,
+    constraints::{Constraints, CONSTRAINTS_FILE},
+    doctor::DoctorCli,
+    document::{Document, DocumentCli, DocumentIdExtractor, DocumentPeriodExtractor},
+    dryrun::{DryRunReport, DryRunRepository, WriteMode},
+    duplicates::DuplicatesCli,
+    error::ErrorFormat,
+    export::ExportCli,
+    graph::GraphCli,
+    import::ImportCli,
+    init::InitCli,
+    journal::{Journal, JournaledRepository, JOURNAL_FILE},
+    logging,
+    merge::MergeCli,
+    output::OutputFormat,
+    pager,
+    policy::IdPolicyPlugin,
+    progress,
+    repository::LocalDocumentRepository,
+    search::SearchCli,
+    shell::{ShellCli, HISTORY_FILE},
+    similarity::SIMILARITY_FILE,
+    stats::StatsCli,
+    template::{TemplateCli, TEMPLATES_DIR},
+    tui::TuiCli,
+    undo::UndoCli,
+    CliCommand,
+};
+use alvidir_plugin_interval::IntervalPlugin;
+use alvidir_plugin_validation::{HttpValidator, ValidationPlugin};
 use anyhow::Result;
-use clap::Parser;
-use tracing::Level;
+use clap::{CommandFactory, FromArgMatches, Parser};
 
 static DEFAULT_EXTENSION: &str = "md";
 
+/// The maximum time to wait for the validation service to respond.
+const VALIDATION_TIMEOUT: Duration = Duration::from_secs(5);
+
 static DEFAULT_CONTEXT: LazyLock<OsString> = LazyLock::new(|| {
     std::env::current_dir()
         .expect("current working directory")
@@ -49,33 +97,369 @@ struct Cli {
         long
     )]
     extension: String,
+
+    /// The base url of the service validating document ids before saving them, overriding the one
+    /// in the constraints of the context, if any.
+    #[arg(global = true, long, env = "ALVIDIR_VALIDATION_URL")]
+    validation_url: Option<String>,
+
+    /// Save documents even if the validation service is unreachable.
+    #[arg(global = true, long, overrides_with = "no_offline")]
+    offline: bool,
+
+    /// Refuse to save documents if the validation service is unreachable, overriding --offline.
+    #[arg(global = true, long, overrides_with = "offline")]
+    no_offline: bool,
+
+    /// Reject any command that would modify the documents.
+    #[arg(global = true, long)]
+    read_only: bool,
+
+    /// Run every constraint without modifying any document, printing the outcome instead.
+    #[arg(global = true, long)]
+    dry_run: bool,
+
+    /// The format to print results in.
+    #[arg(default_value_t, global = true, short, long, value_enum)]
+    output: OutputFormat,
+
+    /// Do not pipe long outputs into a pager.
+    #[arg(global = true, long, overrides_with = "pager")]
+    no_pager: bool,
+
+    /// Pipe long outputs into a pager, overriding --no-pager.
+    #[arg(global = true, long, overrides_with = "no_pager")]
+    pager: bool,
+
+    /// Do not highlight the output with colors, as if NO_COLOR was set.
+    #[arg(global = true, long, overrides_with = "color")]
+    no_color: bool,
+
+    /// Highlight the output with colors, overriding --no-color.
+    #[arg(global = true, long, overrides_with = "no_color")]
+    color: bool,
+
+    /// Do not report the progress of long-running commands.
+    #[arg(global = true, short, long, overrides_with = "no_quiet")]
+    quiet: bool,
+
+    /// Report the progress of long-running commands, overriding --quiet.
+    #[arg(global = true, long, overrides_with = "quiet")]
+    no_quiet: bool,
+
+    /// The format to print errors in, exiting with a distinct code per kind of error.
+    #[arg(default_value_t, global = true, long, value_enum)]
+    errors: ErrorFormat,
+
+    /// The file setting the default value of these options [default: ~/.config/alvidir/config.toml].
+    #[arg(global = true, long, env = "ALVIDIR_CONFIG")]
+    config: Option<PathBuf>,
 }
 
-#[allow(clippy::arc_with_non_send_sync)]
-fn main() -> Result<()> {
+impl Cli {
+    /// Parses the command line arguments again, taking the configured values as the default ones.
+    fn with_config(self) -> Result<(Self, Config)> {
+        let config = match &self.config {
+            Some(path) => Config::load(path)?,
+            None => match Config::default_path().filter(|path| path.exists()) {
+                Some(path) => Config::load(&path)?,
+                None => return Ok((self, Config::default())),
+            },
+        };
+
+        let matches = config.apply(Self::command()).get_matches();
+        Ok((Self::from_arg_matches(&matches)?, config))
+    }
+}
+
+fn main() -> ExitCode {
+    let start = Instant::now();
+    let argv: Vec<String> = std::env::args().skip(1).collect();
     let args = Cli::parse();
+    let errors = args.errors;
+    match args
+        .with_config()
+        .and_then(|(args, config)| execute(args, config, &argv, start))
+    {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => errors.report(&err),
+    }
+}
+
+/// Executes the command in the given arguments, recording it in the journal and the logs.
+fn execute(args: Cli, config: Config, argv: &[String], start: Instant) -> Result<()> {
+    logging::init(&args.context, !args.dry_run);
+    // The configured value of a flag can only set it, so its negated counterpart, if given,
+    // always wins. Every command in a shell would be paged on its own, so none is.
+    pager::init((args.pager || !args.no_pager) && !matches!(args.subcommand, CliCommand::Shell(_)));
+    color::init(args.color || !args.no_color, config.theme);
+    progress::init(args.no_quiet || !args.quiet);
+
+    let document_repo = LocalDocumentRepository {
+        context: args.context.clone(),
+        extension: args.extension.clone(),
+    };
+
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+    let journal = Arc::new(Journal::new(args.context.join(JOURNAL_FILE)).with_clock(clock.clone()));
+    let result = if args.read_only {
+        run(
+            args,
+            ReadOnlyRepository::new(document_repo),
+            journal.clone(),
+            clock.clone(),
+        )
+    } else if args.dry_run {
+        run(
+            args,
+            DryRunRepository::new(document_repo),
+            journal.clone(),
+            clock.clone(),
+        )
+    } else {
+        let checksums = Checksums::load(args.context.join(CHECKSUMS_FILE))?;
+        let document_repo = JournaledRepository::new(document_repo, journal.clone());
+        let document_repo = ChecksummedRepository::new(document_repo, checksums);
+        run(args, document_repo, journal.clone(), clock.clone())
+    };
+
+    // Changes are recorded even if the command failed, since they have been performed anyway.
+    if let Err(err) = journal.commit(argv) {
+        tracing::error!(error = err.to_string(), "committing journal");
+    }
+
+    pager::wait();
+    logging::command(argv, start.elapsed(), &result);
+    result
+}
+
+/// Executes the command in the given arguments on top of the given repository.
+#[allow(clippy::arc_with_non_send_sync)]
+fn run<DocumentRepo>(
+    args: Cli,
+    document_repo: DocumentRepo,
+    journal: Arc<Journal>,
+    clock: Arc<dyn Clock>,
+) -> Result<()>
+where
+    DocumentRepo: 'static
+        + DocumentRepository<Document = Document>
+        + DocumentStore
+        + DocumentTrash
+        + DocumentArchive,
+{
+    let activity_cli = ActivityCli {
+        context: args.context.clone(),
+    };
+
+    let local_repo = Arc::new(LocalDocumentRepository {
+        context: args.context.clone(),
+        extension: args.extension.clone(),
+    });
+
+    let document_repo = Arc::new(document_repo);
+    let documents: Vec<_> = local_repo
+        .ids()
+        .map(LazyDocument::builder(document_repo.clone()))
+        .collect();
+
+    let constraints = Constraints::load(&args.context.join(CONSTRAINTS_FILE))?;
+    let mut schema = Schema::from(Graph::from_iter(documents.clone()))
+        .install(IdPolicyPlugin::new(constraints.policy, local_repo.ids())?);
+
+    // Indexing the periods of every document requires loading them all, so it is done only when
+    // required.
+    if !constraints.simultaneity.allowed {
+        schema = schema.install(
+            IntervalPlugin::new(DocumentPeriodExtractor)
+                .with_nodes(documents)
+                .with_simultaneity(false)
+                .with_overlap(constraints.simultaneity.overlap.into()),
+        );
+    }
+
+    if let Some(url) = args
+        .validation_url
+        .as_ref()
+        .or(constraints.validation.url.as_ref())
+    {
+        let validator = HttpValidator::new(url, VALIDATION_TIMEOUT, DocumentIdExtractor);
+        schema = schema.install(
+            ValidationPlugin::new(validator).with_bypass(args.offline && !args.no_offline),
+        );
+    }
 
-    tracing_subscriber::fmt()
-        .without_time()
-        .with_target(false)
-        .with_max_level(Level::INFO)
-        .with_writer(io::stderr)
-        .init();
+    // Changes are discarded once all constraints have run, so they are never committed.
+    if args.dry_run {
+        schema = schema.with_middleware(DryRun).with_middleware(DryRunReport);
+    }
+
+    // Embedding every document is expensive, so it is done only when required.
+    #[cfg(feature = "embeddings")]
+    let schema = match &args.subcommand {
+        CliCommand::Search(command) if command.is_semantic() => {
+            schema.install(alvidir_plugin_embedding::EmbeddingPlugin::new(
+                alvidir_cli::search::DocumentText,
+                alvidir_plugin_embedding::HashingEmbedder::default(),
+            ))
+        }
+        _ => schema,
+    };
+
+    let schema = Arc::new(schema);
+    let write_mode = if args.read_only {
+        WriteMode::ReadOnly
+    } else if args.dry_run {
+        WriteMode::DryRun
+    } else {
+        WriteMode::Write
+    };
+
+    let apply_cli = ApplyCli {
+        schema: schema.clone(),
+        document_repo: document_repo.clone(),
+    };
+
+    let backup_cli = BackupCli {
+        schema: schema.clone(),
+        document_repo: document_repo.clone(),
+        backups_dir: args.context.join(BACKUPS_DIR),
+        extension: args.extension.clone(),
+        output: args.output,
+        clock,
+        mode: write_mode,
+    };
+
+    let check_cli = CheckCli {
+        schema: schema.clone(),
+        document_repo: document_repo.clone(),
+        integrity: constraints.integrity,
+        output: args.output,
+    };
+
+    let completions_cli = CompletionsCli {
+        command: Cli::command(),
+    };
+
+    let doctor_cli = DoctorCli {
+        schema: schema.clone(),
+        document_repo: document_repo.clone(),
+        output: args.output,
+    };
+
+    let duplicates_cli = DuplicatesCli {
+        schema: schema.clone(),
+        similarity_file: args.context.join(SIMILARITY_FILE),
+        output: args.output,
+    };
+
+    let export_cli = ExportCli {
+        schema: schema.clone(),
+        extension: args.extension.clone(),
+        mode: write_mode,
+    };
+
+    let graph_cli = GraphCli {
+        schema: schema.clone(),
+    };
+
+    let import_cli = ImportCli {
+        schema: schema.clone(),
+        document_repo: document_repo.clone(),
+    };
+
+    let init_cli = InitCli {
+        document_repo: local_repo.clone(),
+    };
 
-    let document_repo = Arc::new(LocalDocumentRepository {
-        context: args.context,
-        extension: args.extension,
+    let merge_cli = MergeCli {
+        schema: schema.clone(),
+        document_repo: document_repo.clone(),
+        extension: args.extension.clone(),
+    };
+
+    let search_cli = SearchCli {
+        schema: schema.clone(),
+        output: args.output,
+    };
+
+    let stats_cli = StatsCli {
+        schema: schema.clone(),
+        document_repo: local_repo.clone(),
+        output: args.output,
+    };
+
+    let template_repo = Arc::new(LocalDocumentRepository {
+        context: args.context.join(TEMPLATES_DIR),
+        extension: args.extension.clone(),
     });
 
-    let graph = Graph::from_iter(document_repo.all());
-    let schema = Arc::new(Schema::from(graph));
+    let template_cli = TemplateCli {
+        template_repo: template_repo.clone(),
+        output: args.output,
+        mode: write_mode,
+    };
+
+    let tui_cli = TuiCli {
+        schema: schema.clone(),
+    };
+
+    let undo_cli = UndoCli {
+        schema: schema.clone(),
+        document_repo: document_repo.clone(),
+        journal: journal.clone(),
+        output: args.output,
+        mode: write_mode,
+    };
+
+    let verify_cli = VerifyCli {
+        schema: schema.clone(),
+        checksums_file: args.context.join(CHECKSUMS_FILE),
+        output: args.output,
+        mode: write_mode,
+    };
 
     let node_cli = DocumentCli {
         schema,
         document_repo,
+        output: args.output,
+        similarity_file: args.context.join(SIMILARITY_FILE),
+        local_repo,
+        template_repo,
     };
 
-    match args.subcommand {
+    let shell_cli = ShellCli {
+        journal,
+        history_file: args.context.join(HISTORY_FILE),
+        mode: write_mode,
+    };
+
+    let dispatch = |subcommand| match subcommand {
+        CliCommand::Activity(command) => activity_cli.execute(command),
+        CliCommand::Apply(command) => apply_cli.execute(command),
+        CliCommand::Backup(command) => backup_cli.execute(command),
+        CliCommand::Check(command) => check_cli.execute(command),
+        CliCommand::Completions(command) => completions_cli.execute(command),
         CliCommand::Doc(command) => node_cli.execute(command),
+        CliCommand::Doctor(command) => doctor_cli.execute(command),
+        CliCommand::Duplicates(command) => duplicates_cli.execute(command),
+        CliCommand::Export(command) => export_cli.execute(command),
+        CliCommand::Graph(command) => graph_cli.execute(command),
+        CliCommand::Import(command) => import_cli.execute(command),
+        CliCommand::Init(command) => init_cli.execute(command),
+        CliCommand::Man(command) => completions_cli.man(command),
+        CliCommand::Merge(command) => merge_cli.execute(command),
+        CliCommand::Search(command) => search_cli.execute(command),
+        CliCommand::Stats(command) => stats_cli.execute(command),
+        CliCommand::Template(command) => template_cli.execute(command),
+        CliCommand::Tui(command) => tui_cli.execute(command),
+        CliCommand::Undo(command) => undo_cli.execute(command),
+        CliCommand::Verify(command) => verify_cli.execute(command),
+        CliCommand::Shell(_) => Err(anyhow::Error::msg("shells cannot be nested")),
+    };
+
+    match args.subcommand {
+        CliCommand::Shell(command) => shell_cli.execute(command, dispatch),
+        subcommand => dispatch(subcommand),
     }
 }
@@ -0,0 +1,148 @@
+use std::{
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use alvidir::{
+    deref::TryDeref,
+    document::{lazy::LazyDocument, DocumentRepository, DocumentStore},
+    graph::{Graph, Source},
+    id::Identify,
+    schema::{ops::save::Save, transaction::Transaction, Schema},
+};
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+
+use crate::{document::Document, logging, repository::LocalDocumentRepository};
+
+/// The strategy to resolve conflicting documents with.
+#[derive(Clone, Copy, ValueEnum)]
+enum MergeStrategy {
+    /// Keep the local version of the document.
+    Ours,
+    /// Keep the incoming version of the document.
+    Theirs,
+    /// Ask which version to keep for each conflict.
+    Interactive,
+}
+
+/// Merge the documents from another directory into the context.
+#[derive(Args)]
+pub struct MergeCommand {
+    /// The directory to merge documents from.
+    source: PathBuf,
+    /// How to resolve documents that differ in both sides.
+    #[arg(long, value_enum, default_value_t = MergeStrategy::Interactive)]
+    strategy: MergeStrategy,
+}
+
+pub struct MergeCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub document_repo: Arc<DocumentRepo>,
+    /// The file's extension.
+    pub extension: String,
+}
+
+impl<DocumentRepo> MergeCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document> + DocumentStore,
+{
+    pub fn execute(&self, command: MergeCommand) -> Result<()> {
+        if !command.source.is_dir() {
+            return Err(anyhow::Error::msg(format!(
+                "{} is not a directory",
+                command.source.display()
+            )));
+        }
+
+        let ours: Graph<Document> = documents(self.schema.read().into_iter());
+        let source_repo = Arc::new(LocalDocumentRepository {
+            context: command.source,
+            extension: self.extension.clone(),
+        });
+
+        let theirs = documents(source_repo.all().collect::<Vec<_>>().iter());
+        let merged = ours.merge(theirs, |ours, theirs| match command.strategy {
+            MergeStrategy::Ours => Ok(ours),
+            MergeStrategy::Theirs => Ok(theirs),
+            MergeStrategy::Interactive => resolve(ours, theirs),
+        })?;
+
+        let changed: Vec<Document> = {
+            let graph = self.schema.read();
+            merged
+                .into_iter()
+                .filter(|document| {
+                    graph
+                        .get(document.id())
+                        .is_none_or(|current| current.try_deref() != Some(*document))
+                })
+                .cloned()
+                .collect()
+        };
+
+        self.schema.transaction().with(|ctx| {
+            for document in &changed {
+                Save::new(LazyDocument::new(
+                    self.document_repo.clone(),
+                    document.clone(),
+                ))
+                .execute(ctx.transaction())?;
+            }
+
+            Ok(())
+        })?;
+
+        let mut stdout = io::stdout().lock();
+        for document in &changed {
+            self.document_repo.save(document)?;
+            logging::audit("merge", document.id().display());
+            writeln!(stdout, "{:?}", document.id())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the graph of all the given documents that could be loaded.
+fn documents<'a, R>(documents: impl Iterator<Item = &'a LazyDocument<R>>) -> Graph<Document>
+where
+    R: 'static + DocumentRepository<Document = Document>,
+{
+    documents
+        .filter_map(|document| document.try_deref().cloned())
+        .collect()
+}
+
+/// Asks the user which version of the conflicting document to keep.
+fn resolve(ours: Document, theirs: Document) -> Result<Document> {
+    let mut stderr = io::stderr().lock();
+    writeln!(stderr, "conflict in {:?}", ours.id())?;
+    writeln!(stderr, "--- ours\n{}", String::from_utf8_lossy(&ours.bytes))?;
+    writeln!(
+        stderr,
+        "--- theirs\n{}",
+        String::from_utf8_lossy(&theirs.bytes)
+    )?;
+
+    let mut stdin = io::stdin().lock();
+    loop {
+        write!(stderr, "keep [o]urs or [t]heirs? ")?;
+        stderr.flush()?;
+
+        let mut answer = String::default();
+        if stdin.read_line(&mut answer)? == 0 {
+            return Err(anyhow::Error::msg("merge aborted"));
+        }
+
+        match answer.trim() {
+            "o" | "ours" => return Ok(ours),
+            "t" | "theirs" => return Ok(theirs),
+            _ => continue,
+        }
+    }
+}
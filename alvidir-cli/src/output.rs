@@ -0,0 +1,89 @@
+use std::io::Write;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::pager;
+
+/// The format in which commands print their results.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// A JSON document.
+    Json,
+    /// A YAML document.
+    Yaml,
+    /// Comma-separated values, one row per record.
+    Csv,
+}
+
+impl OutputFormat {
+    /// Prints all the given records in self's format, using the given closure to render each of
+    /// them as text.
+    pub fn print_all<T, F>(self, records: impl IntoIterator<Item = T>, mut text: F) -> Result<()>
+    where
+        T: Serialize,
+        F: FnMut(&mut dyn Write, T) -> Result<()>,
+    {
+        let mut stdout = pager::stdout();
+        if self == OutputFormat::Text {
+            for record in records {
+                text(&mut stdout, record)?;
+            }
+
+            return Ok(());
+        }
+
+        if self == OutputFormat::Csv {
+            let mut writer = csv::Writer::from_writer(stdout);
+            for record in records {
+                writer.serialize(record)?;
+            }
+
+            writer.flush()?;
+            return Ok(());
+        }
+
+        self.write(&mut stdout, &records.into_iter().collect::<Vec<_>>())
+    }
+
+    /// Prints the given record in self's format, using the given closure to render it as text.
+    pub fn print<T, F>(self, record: T, text: F) -> Result<()>
+    where
+        T: Serialize,
+        F: FnOnce(&mut dyn Write, T) -> Result<()>,
+    {
+        let mut stdout = pager::stdout();
+        if self == OutputFormat::Text {
+            return text(&mut stdout, record);
+        }
+
+        self.write(&mut stdout, &record)
+    }
+
+    /// Writes the given value serialized in self's format.
+    fn write<T>(self, w: &mut dyn Write, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        match self {
+            OutputFormat::Text => unreachable!("text output is rendered by the caller"),
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut *w, value)?;
+                writeln!(w)?;
+            }
+            OutputFormat::Yaml => serde_yaml::to_writer(w, value)?,
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(w);
+                writer.serialize(value)?;
+                writer.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+    process::{Child, Command, Stdio},
+    sync::{Mutex, MutexGuard},
+};
+
+/// The environment variable overriding the pager, taking precedence over `PAGER`.
+const PAGER_ENV: &str = "ALVIDIR_PAGER";
+
+/// The pager to use when no other is configured.
+const DEFAULT_PAGER: &str = "less";
+
+/// The options for `less`, making it quit if the output fits in one screen, keep colors and
+/// leave the output on the screen once closed.
+const DEFAULT_LESS: &str = "FRX";
+
+/// The state of the pager.
+enum State {
+    /// Output goes straight into the standard output.
+    Disabled,
+    /// A pager is spawned as soon as some output requires it.
+    Enabled(String),
+    /// Output goes into the standard input of the running pager.
+    Running(Child),
+}
+
+static STATE: Mutex<State> = Mutex::new(State::Disabled);
+
+/// Enables paging if the standard output is a terminal and a pager is configured.
+pub fn init(enabled: bool) {
+    if !enabled || !io::stdout().is_terminal() {
+        return;
+    }
+
+    let pager = env::var(PAGER_ENV)
+        .or_else(|_| env::var("PAGER"))
+        .unwrap_or(DEFAULT_PAGER.to_string());
+
+    if pager.trim().is_empty() || pager.trim() == "cat" {
+        return;
+    }
+
+    *state() = State::Enabled(pager);
+}
+
+/// Disables paging, unless the pager is already running.
+pub fn disable() {
+    let mut state = state();
+    if let State::Enabled(_) = &*state {
+        *state = State::Disabled;
+    }
+}
+
+/// Returns a writer into the pager, spawning it if required, or into the standard output if
+/// paging is disabled.
+pub fn stdout() -> Box<dyn Write> {
+    let mut state = state();
+    if let State::Enabled(pager) = &*state {
+        *state = spawn(pager).map(State::Running).unwrap_or_else(|err| {
+            tracing::warn!(error = err.to_string(), pager, "spawning pager");
+            State::Disabled
+        });
+    }
+
+    match &*state {
+        State::Running(_) => Box::new(PagerWriter),
+        _ => Box::new(io::stdout().lock()),
+    }
+}
+
+/// Waits for the pager, if any, to be closed by the user.
+pub fn wait() {
+    let State::Running(mut child) = std::mem::replace(&mut *state(), State::Disabled) else {
+        return;
+    };
+
+    drop(child.stdin.take());
+    if let Err(err) = child.wait() {
+        tracing::error!(error = err.to_string(), "waiting for pager");
+    }
+}
+
+/// Writes into the standard input of the running pager.
+struct PagerWriter;
+
+impl Write for PagerWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let State::Running(child) = &mut *state() else {
+            return io::stdout().write(buf);
+        };
+
+        let Some(stdin) = child.stdin.as_mut() else {
+            return Ok(buf.len());
+        };
+
+        match stdin.write(buf) {
+            // The user closed the pager before reading the whole output.
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(buf.len()),
+            result => result,
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let State::Running(child) = &mut *state() else {
+            return io::stdout().flush();
+        };
+
+        match child.stdin.as_mut().map(Write::flush) {
+            Some(Err(err)) if err.kind() != io::ErrorKind::BrokenPipe => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns the state of the pager, recovering it if poisoned.
+fn state() -> MutexGuard<'static, State> {
+    STATE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Spawns the given pager command through the shell.
+fn spawn(pager: &str) -> io::Result<Child> {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(pager).stdin(Stdio::piped());
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", DEFAULT_LESS);
+    }
+
+    command.spawn()
+}
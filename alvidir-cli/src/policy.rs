@@ -0,0 +1,355 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+use alvidir::{graph::Source, prelude::*};
+use anyhow::Result as AnyResult;
+use regex::Regex;
+use serde::Deserialize;
+
+/// The rules every document id must follow, as configured in the
+/// [`CONSTRAINTS_FILE`](crate::constraints::CONSTRAINTS_FILE) of a context.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IdPolicy {
+    /// The maximum amount of characters in an id.
+    pub max_length: Option<usize>,
+    /// The regular expression every id must match as a whole (e.g. '[a-z0-9_/-]+').
+    pub pattern: Option<String>,
+    /// Reject ids whose name, ignoring case, already belongs to another document.
+    pub unique_names: bool,
+}
+
+/// The compiled rules of an [`IdPolicy`].
+struct Rules {
+    max_length: Option<usize>,
+    pattern: Option<Regex>,
+    unique_names: bool,
+}
+
+impl Rules {
+    /// Returns the reason why the given id breaks the rules, if any, given the ids in the source.
+    fn violation<S>(&self, id: &Path, names: &Names, source: &S) -> Option<String>
+    where
+        S: Source,
+        S::Node: Identify<Id = PathBuf>,
+    {
+        let display = id.to_string_lossy();
+        if let Some(max_length) = self
+            .max_length
+            .filter(|&max_length| display.chars().count() > max_length)
+        {
+            return Some(format!(
+                "id {display} is longer than {max_length} characters"
+            ));
+        }
+
+        if let Some(pattern) = self
+            .pattern
+            .as_ref()
+            .filter(|pattern| !pattern.is_match(&display))
+        {
+            return Some(format!("id {display} does not match {}", pattern.as_str()));
+        }
+
+        if self.unique_names {
+            if let Some(other) = names.others(id).find(|&other| source.contains(other)) {
+                return Some(format!(
+                    "id {display} has the same name as {}",
+                    other.display()
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+/// The ids of the documents ever saved into the schema, by name.
+///
+/// Ids are never removed, since the transaction saving or deleting them may fail afterwards, so
+/// any of them must be looked up in the transaction before taking it into account.
+#[derive(Default)]
+struct Names(BTreeMap<String, BTreeSet<PathBuf>>);
+
+impl Names {
+    /// Returns the name of the given id, ignoring case.
+    fn name(id: &Path) -> String {
+        id.file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    }
+
+    fn insert(&mut self, id: PathBuf) {
+        self.0.entry(Self::name(&id)).or_default().insert(id);
+    }
+
+    /// Returns the ids, other than the given one, with the same name.
+    fn others<'a>(&'a self, id: &'a Path) -> impl Iterator<Item = &'a PathBuf> {
+        self.0
+            .get(&Self::name(id))
+            .into_iter()
+            .flatten()
+            .filter(move |other| *other != id)
+    }
+}
+
+/// Implements the [`Plugin`] trait for an [`IdPolicy`], rejecting the save of any node whose id
+/// breaks the policy.
+pub struct IdPolicyPlugin<T> {
+    rules: Rules,
+    names: Names,
+    node: PhantomData<T>,
+}
+
+impl<T> IdPolicyPlugin<T> {
+    /// Compiles the given policy, given the ids of all the nodes in the schema.
+    pub fn new(policy: IdPolicy, ids: impl IntoIterator<Item = PathBuf>) -> AnyResult<Self> {
+        let pattern = policy
+            .pattern
+            .map(|pattern| Regex::new(&format!("^(?:{pattern})$")))
+            .transpose()
+            .map_err(|err| anyhow::Error::msg(format!("invalid id pattern: {err}")))?;
+
+        let mut names = Names::default();
+        if policy.unique_names {
+            ids.into_iter().for_each(|id| names.insert(id));
+        }
+
+        Ok(Self {
+            rules: Rules {
+                max_length: policy.max_length,
+                pattern,
+                unique_names: policy.unique_names,
+            },
+            names,
+            node: PhantomData,
+        })
+    }
+}
+
+impl<T> IdPolicyPlugin<T>
+where
+    T: 'static + Identify<Id = PathBuf> + Clone,
+{
+    fn before_save(
+        ctx: Ctx<T>,
+        target: Target<T>,
+        rules: Res<Rules>,
+        names: Res<Names>,
+    ) -> Result<()> {
+        let Some(id) = target.with(|target| target.id().clone()) else {
+            return Ok(());
+        };
+
+        if let Some(reason) = rules
+            .with(|rules| names.with(|names| rules.violation(&id, names, &ctx)))
+            .flatten()
+            .flatten()
+        {
+            return Err(Error::custom(reason));
+        }
+
+        // Names are only kept track of if they must be unique.
+        if rules.with(|rules| rules.unique_names).unwrap_or_default() {
+            names.with_mut(|names| names.insert(id));
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Plugin<T> for IdPolicyPlugin<T>
+where
+    T: 'static + Identify<Id = PathBuf> + Clone,
+{
+    fn install(self, schema: Schema<T>) -> Schema<T>
+    where
+        T: Identify,
+    {
+        schema
+            .with_resource(self.rules)
+            .with_resource(self.names)
+            .with_trigger(BeforeSave, Self::before_save)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use alvidir::{
+        graph::{Graph, Source},
+        prelude::*,
+        schema::{
+            ops::{delete::Delete, save::Save},
+            transaction::{Context, Transaction},
+        },
+    };
+
+    use crate::document::Document;
+
+    use super::{IdPolicy, IdPolicyPlugin};
+
+    fn document(id: &str) -> Document {
+        Document {
+            path: PathBuf::from(id),
+            bytes: Vec::default(),
+        }
+    }
+
+    fn schema(policy: IdPolicy, ids: &[&str]) -> Schema<Document> {
+        let graph = Graph::from_iter(ids.iter().map(|id| document(id)));
+        let plugin = IdPolicyPlugin::new(policy, ids.iter().map(PathBuf::from))
+            .expect("policy should be valid");
+
+        Schema::from(graph).install(plugin)
+    }
+
+    #[test]
+    fn policy_rules() {
+        struct Test {
+            name: &'static str,
+            policy: IdPolicy,
+            id: &'static str,
+            saved: bool,
+        }
+
+        vec![
+            Test {
+                name: "id within max length",
+                policy: IdPolicy {
+                    max_length: Some(5),
+                    ..Default::default()
+                },
+                id: "sub/b",
+                saved: true,
+            },
+            Test {
+                name: "id longer than max length",
+                policy: IdPolicy {
+                    max_length: Some(5),
+                    ..Default::default()
+                },
+                id: "sub/bc",
+                saved: false,
+            },
+            Test {
+                name: "id matching pattern",
+                policy: IdPolicy {
+                    pattern: Some("[a-z/]+".to_string()),
+                    ..Default::default()
+                },
+                id: "sub/b",
+                saved: true,
+            },
+            Test {
+                name: "id matching pattern partially",
+                policy: IdPolicy {
+                    pattern: Some("[a-z/]+".to_string()),
+                    ..Default::default()
+                },
+                id: "sub/B2",
+                saved: false,
+            },
+            Test {
+                name: "unique name",
+                policy: IdPolicy {
+                    unique_names: true,
+                    ..Default::default()
+                },
+                id: "sub/c",
+                saved: true,
+            },
+            Test {
+                name: "same name ignoring case",
+                policy: IdPolicy {
+                    unique_names: true,
+                    ..Default::default()
+                },
+                id: "other/A",
+                saved: false,
+            },
+            Test {
+                name: "same name without unique names",
+                policy: IdPolicy::default(),
+                id: "other/a",
+                saved: true,
+            },
+            Test {
+                name: "same id as before",
+                policy: IdPolicy {
+                    unique_names: true,
+                    ..Default::default()
+                },
+                id: "a",
+                saved: true,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let schema = schema(test.policy, &["a"]);
+
+            let result = Save::new(document(test.id)).execute(schema.transaction());
+            assert_eq!(result.is_ok(), test.saved, "{}", test.name);
+            assert_eq!(
+                schema.read().contains(&PathBuf::from(test.id)),
+                test.saved,
+                "{}",
+                test.name
+            );
+        });
+    }
+
+    #[test]
+    fn unique_names_follow_the_transaction() {
+        let schema = schema(
+            IdPolicy {
+                unique_names: true,
+                ..Default::default()
+            },
+            &["a/x"],
+        );
+
+        let aborted = |op: &dyn Fn(&Context<'_, Document>) -> Result<()>| {
+            schema
+                .transaction()
+                .with(|ctx| {
+                    op(&ctx)?;
+                    Err::<(), _>(Error::custom("aborted"))
+                })
+                .unwrap_err();
+        };
+
+        // A name saved by a failed transaction is not taken.
+        aborted(&|ctx| Save::new(document("b/y")).execute(ctx.transaction()));
+        assert!(Save::new(document("c/y"))
+            .execute(schema.transaction())
+            .is_ok());
+
+        // A name deleted by a failed transaction is still taken.
+        aborted(&|ctx| Delete::new(PathBuf::from("a/x")).execute(ctx.transaction()));
+        assert!(Save::new(document("c/x"))
+            .execute(schema.transaction())
+            .is_err());
+
+        // Names taken within the same transaction count.
+        assert!(schema
+            .transaction()
+            .with(|ctx| {
+                Save::new(document("d/z")).execute(ctx.transaction())?;
+                Save::new(document("e/z")).execute(ctx.transaction())
+            })
+            .is_err());
+
+        Delete::new(PathBuf::from("a/x"))
+            .execute(schema.transaction())
+            .unwrap();
+
+        assert!(Save::new(document("c/x"))
+            .execute(schema.transaction())
+            .is_ok());
+    }
+}
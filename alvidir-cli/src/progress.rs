@@ -0,0 +1,77 @@
+use std::{
+    io::{self, IsTerminal},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// The time in between redraws of a progress bar.
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Whether progress bars are drawn at all.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enables progress bars if the standard error is a terminal.
+pub fn init(enabled: bool) {
+    ENABLED.store(enabled && io::stderr().is_terminal(), Ordering::Relaxed);
+}
+
+/// The progress of a long-running operation, drawn into the standard error if enabled.
+pub struct Progress(Option<ProgressBar>);
+
+impl Progress {
+    /// Returns a progress bar for the given amount of steps.
+    pub fn new(message: &'static str, len: usize) -> Self {
+        Self::with(message, || {
+            ProgressBar::new(len as u64).with_style(
+                ProgressStyle::with_template("{msg} [{bar:40}] {pos}/{len} ({eta})")
+                    .unwrap_or_else(|_| ProgressStyle::default_bar())
+                    .progress_chars("=> "),
+            )
+        })
+    }
+
+    /// Returns a progress indicator for an unknown amount of steps.
+    pub fn spinner(message: &'static str) -> Self {
+        Self::with(message, || {
+            ProgressBar::new_spinner().with_style(
+                ProgressStyle::with_template("{spinner} {msg} {pos}")
+                    .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+            )
+        })
+    }
+
+    fn with(message: &'static str, bar: impl FnOnce() -> ProgressBar) -> Self {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return Self(None);
+        }
+
+        let bar = bar().with_message(message);
+        bar.enable_steady_tick(TICK_INTERVAL);
+        Self(Some(bar))
+    }
+
+    /// Advances the progress by one step.
+    pub fn inc(&self) {
+        if let Some(bar) = &self.0 {
+            bar.inc(1);
+        }
+    }
+
+    /// Calls the given closure with the progress hidden, so its output is not overwritten.
+    pub fn suspend<T>(&self, f: impl FnOnce() -> T) -> T {
+        match &self.0 {
+            Some(bar) => bar.suspend(f),
+            None => f(),
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if let Some(bar) = &self.0 {
+            bar.finish_and_clear();
+        }
+    }
+}
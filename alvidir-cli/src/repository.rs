@@ -1,14 +1,41 @@
-use std::{fs, path::PathBuf, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
 
 use alvidir::{
-    document::{lazy::LazyDocument, DocumentRepository},
+    document::{
+        lazy::LazyDocument, DocumentArchive, DocumentRepository, DocumentStore, DocumentTrash,
+    },
     id::Identify,
+    schema::{Error, Result},
 };
 use ignore::Walk;
 use regex::Regex;
+use serde::Serialize;
 
 use crate::document::Document;
 
+/// The directory, relative to the context, in which deleted documents are kept.
+const TRASH_DIR: &str = ".trash";
+
+/// The file, relative to the context, listing the id of the archived documents.
+pub const ARCHIVE_FILE: &str = ".alvidir/archive.json";
+
+/// A summary of the documents in a [`LocalDocumentRepository`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct RepositoryStats {
+    /// The amount of documents in the repository.
+    pub documents: usize,
+    /// The total size, in bytes, of all the documents in the repository.
+    pub bytes: u64,
+    /// The amount of documents in the trash.
+    pub trashed: usize,
+}
+
 /// Implements the [`DocumentRepository`] trait taking as datasource the given local directory.
 pub struct LocalDocumentRepository {
     /// The base path in which the repository has to look up for files.
@@ -21,7 +48,7 @@ impl DocumentRepository for LocalDocumentRepository {
     type Document = Document;
 
     fn find_by_id(&self, id: &<Self::Document as Identify>::Id) -> Option<Self::Document> {
-        let path = self.context.join(id).with_extension(&self.extension);
+        let path = self.path(&self.context, id);
 
         fs::read(&path)
             .inspect_err(|err| {
@@ -33,15 +60,189 @@ impl DocumentRepository for LocalDocumentRepository {
                     "finding document by id"
                 )
             })
-            .map(|bytes| Document { path, bytes })
+            .map(|bytes| Document {
+                path: id.clone(),
+                bytes,
+            })
             .ok()
     }
 }
 
+impl DocumentStore for LocalDocumentRepository {
+    fn save(&self, document: &Self::Document) -> Result<()> {
+        let path = self.path(&self.context, document.id());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&path, &document.bytes).map_err(Error::from)
+    }
+}
+
+impl DocumentTrash for LocalDocumentRepository {
+    fn trash(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        let trash = self.path(&self.context.join(TRASH_DIR), id);
+        if trash.exists() {
+            return Err(Error::custom(format!(
+                "document {} is already in the trash, purge it first",
+                id.display()
+            )));
+        }
+
+        Self::rename(&self.path(&self.context, id), &trash)
+    }
+
+    fn restore(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        let path = self.path(&self.context, id);
+        if path.exists() {
+            return Err(Error::custom(format!(
+                "document {} already exists",
+                id.display()
+            )));
+        }
+
+        let trash = self.context.join(TRASH_DIR);
+        Self::rename(&self.path(&trash, id), &path)
+    }
+
+    fn purge(&self) -> Result<()> {
+        let trash = self.context.join(TRASH_DIR);
+        if !trash.exists() {
+            return Ok(());
+        }
+
+        fs::remove_dir_all(&trash).map_err(Error::from)
+    }
+
+    fn trashed(&self) -> Vec<<Self::Document as Identify>::Id> {
+        let trash = self.context.join(TRASH_DIR);
+        if !trash.exists() {
+            return Vec::default();
+        }
+
+        self.walk(trash).collect()
+    }
+}
+
+impl DocumentArchive for LocalDocumentRepository {
+    fn archive(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        if !self.contains(id) {
+            return Err(Error::custom(format!(
+                "document {} does not exist",
+                id.display()
+            )));
+        }
+
+        let mut archived = self.read_archive()?;
+        archived.insert(id.clone());
+        self.write_archive(&archived)
+    }
+
+    fn unarchive(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        let mut archived = self.read_archive()?;
+        if !archived.remove(id) {
+            return Err(Error::custom(format!(
+                "document {} is not archived",
+                id.display()
+            )));
+        }
+
+        self.write_archive(&archived)
+    }
+
+    fn archived(&self) -> Vec<<Self::Document as Identify>::Id> {
+        self.read_archive()
+            .inspect_err(|err| tracing::error!(error = err.to_string(), "reading archive"))
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    }
+}
+
 impl LocalDocumentRepository {
     /// Returns an iterator of [`LazyDocument`].
     pub fn all(self: &Arc<Self>) -> impl Iterator<Item = LazyDocument<Self>> + '_ {
-        Walk::new(&self.context)
+        self.ids().map(LazyDocument::builder(self.clone()))
+    }
+
+    /// Returns an iterator over the ids of all the documents in the repository.
+    pub fn ids(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        // A missing context is just an empty one (e.g. before being initialized).
+        self.context
+            .exists()
+            .then(|| self.walk(self.context.clone()))
+            .into_iter()
+            .flatten()
+    }
+
+    /// Returns the last modification time of every document in the repository, by id.
+    pub fn modified(&self) -> BTreeMap<PathBuf, SystemTime> {
+        self.ids()
+            .filter_map(|id| {
+                let modified = fs::metadata(self.path(&self.context, &id))
+                    .and_then(|metadata| metadata.modified())
+                    .ok()?;
+
+                Some((id, modified))
+            })
+            .collect()
+    }
+
+    /// Returns true if, and only if, the document with the given id is in the repository.
+    pub fn contains(&self, id: &Path) -> bool {
+        self.path(&self.context, id).is_file()
+    }
+
+    /// Returns the summary of the documents in the repository, without loading them.
+    pub fn stats(&self) -> RepositoryStats {
+        let mut stats = RepositoryStats {
+            trashed: self.trashed().len(),
+            ..Default::default()
+        };
+
+        for id in self.ids() {
+            stats.documents += 1;
+            stats.bytes += fs::metadata(self.path(&self.context, &id))
+                .map(|metadata| metadata.len())
+                .unwrap_or_default();
+        }
+
+        stats
+    }
+
+    /// Returns the id of the archived documents, as listed in the [`ARCHIVE_FILE`].
+    fn read_archive(&self) -> Result<BTreeSet<PathBuf>> {
+        let path = self.context.join(ARCHIVE_FILE);
+        if !path.exists() {
+            return Ok(BTreeSet::default());
+        }
+
+        let content = fs::read_to_string(&path)?;
+        serde_json::from_str(&content)
+            .map_err(|err| Error::custom(format!("parsing {}: {err}", path.display())))
+    }
+
+    /// Overwrites the [`ARCHIVE_FILE`] with the given ids.
+    fn write_archive(&self, archived: &BTreeSet<PathBuf>) -> Result<()> {
+        let path = self.context.join(ARCHIVE_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(archived).map_err(Error::custom)?;
+        fs::write(&path, content + "\n").map_err(Error::from)
+    }
+
+    /// Returns the path of the file for the given id relative to the given base directory.
+    fn path(&self, base: &Path, id: &Path) -> PathBuf {
+        base.join(id).with_extension(&self.extension)
+    }
+
+    /// Returns an iterator over the ids of all the documents in the given base directory.
+    fn walk(&self, base: PathBuf) -> impl Iterator<Item = PathBuf> + '_ {
+        // Hidden files are not skipped at the top level, this allows walking hidden
+        // directories (like the trash) while still ignoring them from the context.
+        Walk::new(&base)
             .filter_map(move |entry| {
                 if let Err(err) = &entry {
                     tracing::error!(
@@ -68,7 +269,7 @@ impl LocalDocumentRepository {
                 let path = entry
                     .path()
                     .with_extension("")
-                    .strip_prefix(&self.context)
+                    .strip_prefix(&base)
                     .map(ToOwned::to_owned);
 
                 if let Err(err) = &path {
@@ -82,6 +283,91 @@ impl LocalDocumentRepository {
 
                 path.ok()
             })
-            .map(LazyDocument::builder(self.clone()))
+    }
+
+    /// Moves the file in the given source path to the given destination, creating any missing
+    /// directory.
+    fn rename(from: &Path, to: &Path) -> Result<()> {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::rename(from, to).map_err(Error::from)
+    }
+}
+
+#[cfg(test)]
+pub mod fixtures {
+    use std::{path::Path, sync::Arc};
+
+    use alvidir::{document::lazy::LazyDocument, graph::Graph, schema::Schema};
+
+    use super::LocalDocumentRepository;
+
+    /// Returns a repository of markdown documents in the given context, and the schema holding
+    /// all of them.
+    #[allow(clippy::arc_with_non_send_sync)]
+    pub fn local_schema(
+        context: &Path,
+    ) -> (
+        Arc<LocalDocumentRepository>,
+        Arc<Schema<LazyDocument<LocalDocumentRepository>>>,
+    ) {
+        let document_repo = Arc::new(LocalDocumentRepository {
+            context: context.to_path_buf(),
+            extension: "md".to_string(),
+        });
+
+        let graph = Graph::from_iter(
+            document_repo
+                .ids()
+                .map(LazyDocument::builder(document_repo.clone())),
+        );
+
+        (document_repo, Arc::new(Schema::from(graph)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use alvidir::document::conformance;
+
+    use crate::document::Document;
+
+    use super::LocalDocumentRepository;
+
+    #[test]
+    fn local_repository_holds_all_laws() {
+        let context = tempfile::tempdir().unwrap();
+        conformance::check(
+            || {
+                // Each law runs against an empty repository.
+                let context = tempfile::tempdir_in(context.path()).unwrap().keep();
+                LocalDocumentRepository {
+                    context,
+                    extension: "md".to_string(),
+                }
+            },
+            &[
+                Document {
+                    path: PathBuf::from("a"),
+                    bytes: b"a".to_vec(),
+                },
+                Document {
+                    path: PathBuf::from("sub/b"),
+                    bytes: b"b".to_vec(),
+                },
+                Document {
+                    path: PathBuf::from("sub/b"),
+                    bytes: b"c".to_vec(),
+                },
+                Document {
+                    path: PathBuf::from("sub/deep/d"),
+                    bytes: Vec::default(),
+                },
+            ],
+        );
     }
 }
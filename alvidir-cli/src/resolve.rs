@@ -0,0 +1,285 @@
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+
+use crate::{error::CliError, similarity::levenshtein};
+
+/// The maximum amount of candidates listed when an id is ambiguous.
+const MAX_CANDIDATES: usize = 10;
+
+/// How loosely an id may stand for an existing one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Matching {
+    /// Besides the exact id, prefixes, names and misspellings are accepted, prompting on
+    /// ambiguity.
+    Loose,
+    /// Only the exact id is accepted, any other match being suggested instead, so commands
+    /// modifying documents never pick the wrong one.
+    Strict,
+}
+
+/// Returns the id among the given ones that the given id stands for.
+///
+/// Besides the exact id, any unambiguous prefix (e.g. 'su' for 'sub/b') is accepted, as well as
+/// the name of the document (e.g. 'B' for 'sub/b'), ignoring case, or a misspelling of it. If
+/// several ids match equally well the user is prompted to choose one, if interactive. Strict
+/// matching accepts the exact id only.
+///
+/// An id that matches none is returned as is, so the caller decides how to handle a missing
+/// document.
+pub fn resolve_id<'a>(
+    id: &Path,
+    ids: impl IntoIterator<Item = &'a Path>,
+    matching: Matching,
+) -> Result<PathBuf> {
+    let interactive = io::stdin().is_terminal() && io::stderr().is_terminal();
+    let resolved = resolve(id, ids.into_iter().collect(), matching, interactive)?;
+    if resolved != id {
+        writeln!(
+            io::stderr(),
+            "{} resolved as {}",
+            id.display(),
+            resolved.display()
+        )?;
+    }
+
+    Ok(resolved)
+}
+
+/// Returns the id among the given ones that the given id stands for, as in [`resolve_id`].
+fn resolve(id: &Path, ids: Vec<&Path>, matching: Matching, interactive: bool) -> Result<PathBuf> {
+    if ids.contains(&id) {
+        return Ok(id.to_path_buf());
+    }
+
+    let query = id.to_string_lossy().to_lowercase();
+    let prefixed: Vec<&Path> = ids
+        .iter()
+        .copied()
+        .filter(|candidate| {
+            candidate
+                .to_string_lossy()
+                .starts_with(&*id.to_string_lossy())
+        })
+        .collect();
+
+    if !prefixed.is_empty() {
+        return match matching {
+            Matching::Loose => choose(id, prefixed, interactive),
+            Matching::Strict => Err(CliError::InexactId {
+                id: id.to_path_buf(),
+                suggestions: listed(prefixed),
+            }
+            .into()),
+        };
+    }
+
+    // The distance of each id to the query, as the closest of the whole id and its name.
+    let distance = |candidate: &Path| {
+        let name = candidate
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        let whole = candidate.to_string_lossy().to_lowercase();
+        levenshtein(&query, &name).min(levenshtein(&query, &whole))
+    };
+
+    // Short queries are not misspelled, but different words.
+    let threshold = query.chars().count() / 3;
+    let Some(best) = ids.iter().map(|candidate| distance(candidate)).min() else {
+        return Ok(id.to_path_buf());
+    };
+
+    if best > threshold {
+        return Ok(id.to_path_buf());
+    }
+
+    let closest = ids
+        .into_iter()
+        .filter(|candidate| distance(candidate) == best)
+        .collect();
+
+    match matching {
+        Matching::Loose => choose(id, closest, interactive),
+        Matching::Strict => Err(CliError::InexactId {
+            id: id.to_path_buf(),
+            suggestions: listed(closest),
+        }
+        .into()),
+    }
+}
+
+/// Returns the given candidates as printed, sorted and up to [`MAX_CANDIDATES`].
+fn listed(mut candidates: Vec<&Path>) -> Vec<String> {
+    candidates.sort();
+    let mut listed: Vec<_> = candidates
+        .iter()
+        .take(MAX_CANDIDATES)
+        .map(|candidate| candidate.display().to_string())
+        .collect();
+
+    if candidates.len() > MAX_CANDIDATES {
+        listed.push(format!("and {} more", candidates.len() - MAX_CANDIDATES));
+    }
+
+    listed
+}
+
+/// Returns the only given candidate, prompting the user to choose one if there are many and
+/// interactive.
+fn choose(id: &Path, mut candidates: Vec<&Path>, interactive: bool) -> Result<PathBuf> {
+    candidates.sort();
+    if let [candidate] = candidates.as_slice() {
+        return Ok(candidate.to_path_buf());
+    }
+
+    if !interactive {
+        return Err(CliError::AmbiguousId {
+            id: id.to_path_buf(),
+            candidates: listed(candidates),
+        }
+        .into());
+    }
+
+    let mut stderr = io::stderr();
+    writeln!(stderr, "ambiguous id {}:", id.display())?;
+    for (index, candidate) in candidates.iter().enumerate() {
+        writeln!(stderr, "  {}) {}", index + 1, candidate.display())?;
+    }
+
+    write!(stderr, "choose one [1-{}]: ", candidates.len())?;
+    stderr.flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    answer
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|index| index.checked_sub(1))
+        .and_then(|index| candidates.get(index))
+        .map(|candidate| candidate.to_path_buf())
+        .ok_or_else(|| anyhow::Error::msg(format!("invalid choice {:?}", answer.trim())))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::error::ErrorKind;
+
+    use super::{resolve, Matching};
+
+    #[test]
+    fn resolve_existing_id() {
+        struct Test {
+            name: &'static str,
+            id: &'static str,
+            ids: Vec<&'static str>,
+            matching: Matching,
+            want: Result<&'static str, ErrorKind>,
+        }
+
+        vec![
+            Test {
+                name: "exact id beats prefix",
+                id: "a",
+                ids: vec!["ab", "a", "abc"],
+                matching: Matching::Loose,
+                want: Ok("a"),
+            },
+            Test {
+                name: "unique prefix",
+                id: "su",
+                ids: vec!["a", "sub/b"],
+                matching: Matching::Loose,
+                want: Ok("sub/b"),
+            },
+            Test {
+                name: "ambiguous prefix",
+                id: "sub",
+                ids: vec!["sub/b", "sub/c"],
+                matching: Matching::Loose,
+                want: Err(ErrorKind::Conflict),
+            },
+            Test {
+                name: "name match",
+                id: "B",
+                ids: vec!["a", "sub/b"],
+                matching: Matching::Loose,
+                want: Ok("sub/b"),
+            },
+            Test {
+                name: "no match",
+                id: "z",
+                ids: vec!["a", "sub/b"],
+                matching: Matching::Loose,
+                want: Ok("z"),
+            },
+            Test {
+                name: "strict exact id",
+                id: "sub/b",
+                ids: vec!["sub/b", "sub/bc"],
+                matching: Matching::Strict,
+                want: Ok("sub/b"),
+            },
+            Test {
+                name: "misspelled name",
+                id: "notes",
+                ids: vec!["a", "sub/nodes"],
+                matching: Matching::Loose,
+                want: Ok("sub/nodes"),
+            },
+            Test {
+                name: "misspelling beyond threshold",
+                id: "ab",
+                ids: vec!["ac", "sub/b"],
+                matching: Matching::Loose,
+                want: Ok("ab"),
+            },
+            Test {
+                name: "ambiguous misspelling",
+                id: "notes",
+                ids: vec!["nodes", "sub/votes"],
+                matching: Matching::Loose,
+                want: Err(ErrorKind::Conflict),
+            },
+            Test {
+                name: "strict misspelling",
+                id: "notes",
+                ids: vec!["a", "sub/nodes"],
+                matching: Matching::Strict,
+                want: Err(ErrorKind::NotFound),
+            },
+            Test {
+                name: "strict prefix",
+                id: "su",
+                ids: vec!["a", "sub/b"],
+                matching: Matching::Strict,
+                want: Err(ErrorKind::NotFound),
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let got = resolve(
+                Path::new(test.id),
+                test.ids.into_iter().map(Path::new).collect(),
+                test.matching,
+                false,
+            )
+            .map_err(|err| ErrorKind::of(&err));
+
+            assert_eq!(
+                got,
+                test.want.map(PathBuf::from),
+                "{} got = {got:?}, want = {:?}",
+                test.name,
+                test.want
+            );
+        });
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use alvidir::{
+    deref::TryDeref,
+    document::{lazy::LazyDocument, DocumentRepository},
+    id::Identify,
+    schema::Schema,
+};
+use anyhow::Result;
+use clap::Args;
+
+use crate::{
+    color::{paint, Role},
+    document::{Document, DocumentRecord},
+    output::OutputFormat,
+};
+
+/// Search documents by their content.
+#[derive(Args)]
+pub struct SearchCommand {
+    /// The text to search for.
+    query: String,
+    /// Rank documents by semantic similarity instead of matching the text.
+    #[cfg(feature = "embeddings")]
+    #[arg(long)]
+    semantic: bool,
+    /// The maximum amount of documents to show.
+    #[arg(long, default_value_t = 10)]
+    limit: usize,
+}
+
+impl SearchCommand {
+    /// Returns true if, and only if, the search is a semantic one.
+    pub fn is_semantic(&self) -> bool {
+        #[cfg(feature = "embeddings")]
+        return self.semantic;
+        #[cfg(not(feature = "embeddings"))]
+        return false;
+    }
+}
+
+pub struct SearchCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    /// The format to print results in.
+    pub output: OutputFormat,
+}
+
+impl<DocumentRepo> SearchCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    pub fn execute(&self, command: SearchCommand) -> Result<()> {
+        #[cfg(feature = "embeddings")]
+        if command.semantic {
+            return self.semantic(command);
+        }
+
+        let query = command.query.to_lowercase();
+        let graph = self.schema.read();
+        let records = graph
+            .into_iter()
+            .filter(|document| {
+                document_text(document)
+                    .map(|text| text.to_lowercase().contains(&query))
+                    .unwrap_or_default()
+            })
+            .take(command.limit)
+            .map(|document| DocumentRecord {
+                id: document.id(),
+                document,
+            });
+
+        self.output.print_all(records, |stdout, record| {
+            writeln!(
+                stdout,
+                "{}",
+                paint(Role::Id, format_args!("{:?}", record.id))
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Prints the documents that are the nearest to the query, along with their similarity.
+    #[cfg(feature = "embeddings")]
+    fn semantic(&self, command: SearchCommand) -> Result<()> {
+        use alvidir::{deref::With, schema::resource::Res};
+        use alvidir_plugin_embedding::{Embedder, EmbeddingIndex, HashingEmbedder};
+
+        use crate::similarity::SimilarityRecord;
+
+        let index = Res::<EmbeddingIndex<std::path::PathBuf>>::from(self.schema.resources());
+        let embedder = Res::<HashingEmbedder>::from(self.schema.resources());
+
+        let nearest = (index, embedder)
+            .with(|(index, embedder)| {
+                index
+                    .nearest(&embedder.embed(&command.query), command.limit)
+                    .into_iter()
+                    .map(|(document_id, similarity)| (document_id.clone(), similarity))
+                    .collect::<Vec<_>>()
+            })
+            .ok_or(anyhow::Error::msg("embedding index must be installed"))?;
+
+        let records = nearest
+            .into_iter()
+            .map(|(id, similarity)| SimilarityRecord { id, similarity });
+
+        self.output.print_all(records, |stdout, record| {
+            writeln!(
+                stdout,
+                "{}  {}",
+                paint(Role::Muted, format_args!("{:.3}", record.similarity)),
+                paint(Role::Id, format_args!("{:?}", record.id))
+            )?;
+            Ok(())
+        })
+    }
+}
+
+/// Returns the text of the given document, which is its name followed by its content.
+fn document_text<DocumentRepo>(document: &LazyDocument<DocumentRepo>) -> Option<String>
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    let document = document.try_deref()?;
+    let name = document
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+
+    Some(format!(
+        "{name}\n{}",
+        String::from_utf8_lossy(&document.bytes)
+    ))
+}
+
+/// Extracts the text of documents to be embedded.
+#[cfg(feature = "embeddings")]
+pub struct DocumentText;
+
+#[cfg(feature = "embeddings")]
+impl<DocumentRepo> alvidir::property::Extract<LazyDocument<DocumentRepo>> for DocumentText
+where
+    DocumentRepo: DocumentRepository<Document = Document>,
+{
+    type Target = String;
+
+    fn all(&self, document: &LazyDocument<DocumentRepo>) -> Vec<Self::Target> {
+        document_text(document).into_iter().collect()
+    }
+}
@@ -0,0 +1,366 @@
+use std::{
+    io::{self, IsTerminal, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::Result;
+use clap::{Args, CommandFactory, FromArgMatches, Parser};
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, Editor, Helper,
+};
+
+use crate::{dryrun::WriteMode, journal::Journal, CliCommand};
+
+/// The prompt printed before reading each command, if interactive.
+const PROMPT: &str = "alvidir> ";
+
+/// The file, relative to the context, in which the commands of interactive shells are kept.
+pub const HISTORY_FILE: &str = ".alvidir/history";
+
+/// Run commands read from the standard input, one per line, keeping the documents loaded in
+/// between.
+#[derive(Args)]
+pub struct ShellCommand;
+
+/// Run any of the following commands, or 'exit' to quit the shell.
+#[derive(Parser)]
+#[command(name = "alvidir", no_binary_name = true, disable_version_flag = true)]
+struct ShellLine {
+    #[command(subcommand)]
+    command: CliCommand,
+}
+
+impl ShellLine {
+    /// Returns the command line parser, without the shell command itself.
+    fn parser() -> clap::Command {
+        Self::command().mut_subcommand("shell", |shell| shell.hide(true))
+    }
+
+    /// Parses the given words into the command they stand for, which cannot be a shell nor watch
+    /// for changes, since neither would ever return to the shell.
+    fn parse(parser: &mut clap::Command, words: &[String]) -> clap::error::Result<CliCommand> {
+        let matches = parser.try_get_matches_from_mut(words)?;
+        let watching = matches
+            .subcommand_matches("doc")
+            .and_then(|doc| doc.subcommand_matches("list"))
+            .is_some_and(|list| list.get_flag("watch"));
+
+        if watching {
+            return Err(parser.error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--watch cannot be used in a shell",
+            ));
+        }
+
+        match Self::from_arg_matches(&matches)?.command {
+            CliCommand::Shell(_) => Err(parser.error(
+                clap::error::ErrorKind::InvalidSubcommand,
+                "shells cannot be nested",
+            )),
+            command => Ok(command),
+        }
+    }
+}
+
+/// Completes the commands and options of the shell.
+struct ShellHelper {
+    parser: clap::Command,
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let start = line
+            .rfind(char::is_whitespace)
+            .map(|index| index + 1)
+            .unwrap_or_default();
+
+        // The deepest subcommand among the words before the one being completed.
+        let mut command = &self.parser;
+        for word in line[..start].split_whitespace() {
+            if let Some(subcommand) = command.find_subcommand(word) {
+                command = subcommand;
+            }
+        }
+
+        let prefix = &line[start..];
+        let subcommands = command
+            .get_subcommands()
+            .filter(|subcommand| !subcommand.is_hide_set())
+            .map(|subcommand| subcommand.get_name().to_string());
+
+        let options = command
+            .get_arguments()
+            .filter(|arg| !arg.is_hide_set())
+            .filter_map(|arg| arg.get_long())
+            .map(|long| format!("--{long}"));
+
+        let mut candidates: Vec<_> = subcommands
+            .chain(options)
+            .filter(|candidate| candidate.starts_with(prefix))
+            .collect();
+
+        candidates.sort();
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// The source of the lines of a shell.
+enum Lines {
+    /// An interactive editor, with history and completion.
+    Editor(Box<Editor<ShellHelper, DefaultHistory>>),
+    /// The standard input, as is.
+    Stdin,
+}
+
+impl Lines {
+    /// Returns the next line, if any.
+    fn next(&mut self) -> Result<Option<String>> {
+        match self {
+            Lines::Editor(editor) => match editor.readline(PROMPT) {
+                Ok(line) => {
+                    editor.add_history_entry(line.as_str())?;
+                    Ok(Some(line))
+                }
+                // An interrupted line is discarded, as in any other shell.
+                Err(ReadlineError::Interrupted) => Ok(Some(String::default())),
+                Err(ReadlineError::Eof) => Ok(None),
+                Err(err) => Err(err.into()),
+            },
+            Lines::Stdin => {
+                // The standard input is not locked in between, since commands may read from it
+                // too.
+                let mut line = String::new();
+                if io::stdin().read_line(&mut line)? == 0 {
+                    return Ok(None);
+                }
+
+                Ok(Some(line))
+            }
+        }
+    }
+}
+
+pub struct ShellCli {
+    pub journal: Arc<Journal>,
+    /// The file in which the commands of interactive shells are kept.
+    pub history_file: PathBuf,
+    /// Whether the history may be written.
+    pub mode: WriteMode,
+}
+
+impl ShellCli {
+    /// Executes every command read from the standard input through the given closure.
+    ///
+    /// Global options are the ones the shell was run with, so they are not accepted per line.
+    /// Empty lines and lines starting with '#' are ignored, and 'exit' (or 'quit') ends the shell.
+    /// If interactive, lines are read with history and completion.
+    pub fn execute<F>(&self, _: ShellCommand, dispatch: F) -> Result<()>
+    where
+        F: Fn(CliCommand) -> Result<()>,
+    {
+        let interactive = io::stdin().is_terminal();
+        let mut stderr = io::stderr();
+        let mut parser = ShellLine::parser();
+
+        let mut lines = if interactive {
+            let mut editor = Editor::new()?;
+            editor.set_helper(Some(ShellHelper {
+                parser: parser.clone(),
+            }));
+
+            // A missing history is not an error, but the first shell.
+            let _ = editor.load_history(&self.history_file);
+            Lines::Editor(Box::new(editor))
+        } else {
+            Lines::Stdin
+        };
+
+        let mut commands = 0;
+        let mut failed = 0;
+        while let Some(line) = lines.next()? {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if matches!(line, "exit" | "quit") {
+                break;
+            }
+
+            commands += 1;
+            let Some(words) = shlex::split(line) else {
+                failed += 1;
+                writeln!(stderr, "Error: unbalanced quotes")?;
+                continue;
+            };
+
+            // Parsing errors are already prefixed, and help requests are not errors at all.
+            let command = match ShellLine::parse(&mut parser, &words) {
+                Ok(command) => command,
+                Err(err) => {
+                    failed += usize::from(err.use_stderr());
+                    err.print()?;
+                    continue;
+                }
+            };
+
+            if let Err(err) = dispatch(command) {
+                failed += 1;
+                writeln!(stderr, "Error: {err}")?;
+            }
+
+            // Each command is journaled on its own, so they can be undone one by one.
+            if let Err(err) = self.journal.commit(&words) {
+                tracing::error!(error = err.to_string(), "committing journal");
+            }
+        }
+
+        if let Lines::Editor(editor) = &mut lines {
+            self.save_history(editor)?;
+        }
+
+        if !interactive && failed > 0 {
+            return Err(anyhow::Error::msg(format!(
+                "{failed} of {commands} commands failed"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Writes the history of the given editor into the history file, if allowed.
+    fn save_history(&self, editor: &mut Editor<ShellHelper, DefaultHistory>) -> Result<()> {
+        // The history is not worth reporting as a write in dry runs.
+        if self.mode != WriteMode::Write {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.history_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        editor.save_history(&self.history_file)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustyline::{completion::Completer, history::DefaultHistory, Context};
+
+    use super::{ShellHelper, ShellLine};
+
+    #[test]
+    fn parse_shell_line() {
+        struct Test {
+            name: &'static str,
+            line: &'static str,
+            parsed: bool,
+        }
+
+        vec![
+            Test {
+                name: "command",
+                line: "doc list",
+                parsed: true,
+            },
+            Test {
+                name: "unknown command",
+                line: "bogus",
+                parsed: false,
+            },
+            Test {
+                name: "nested shell",
+                line: "shell",
+                parsed: false,
+            },
+            Test {
+                name: "watching list",
+                line: "doc list --watch",
+                parsed: false,
+            },
+            Test {
+                name: "watching list of a document",
+                line: "doc a ls --watch",
+                parsed: false,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let words: Vec<_> = test.line.split_whitespace().map(String::from).collect();
+            let result = ShellLine::parse(&mut ShellLine::parser(), &words);
+            assert_eq!(result.is_ok(), test.parsed, "{}", test.name);
+        });
+    }
+
+    #[test]
+    fn complete_shell_line() {
+        struct Test {
+            name: &'static str,
+            line: &'static str,
+            start: usize,
+            candidates: Vec<&'static str>,
+        }
+
+        vec![
+            Test {
+                name: "command",
+                line: "do",
+                start: 0,
+                candidates: vec!["doc", "doctor"],
+            },
+            Test {
+                name: "subcommand",
+                line: "doc li",
+                start: 4,
+                candidates: vec!["list"],
+            },
+            Test {
+                name: "option",
+                line: "backup create --k",
+                start: 14,
+                candidates: vec!["--keep"],
+            },
+            Test {
+                name: "nested shell",
+                line: "she",
+                start: 0,
+                candidates: vec![],
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let helper = ShellHelper {
+                parser: ShellLine::parser(),
+            };
+
+            let history = DefaultHistory::new();
+            let (start, candidates) = helper
+                .complete(test.line, test.line.len(), &Context::new(&history))
+                .unwrap();
+
+            assert_eq!(start, test.start, "{}", test.name);
+            assert_eq!(candidates, test.candidates, "{}", test.name);
+        });
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use alvidir::{deref::TryDeref, id::Identify};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::document::Document;
+
+/// The file, relative to the context, in which the similarity weights are configured.
+pub const SIMILARITY_FILE: &str = ".alvidir/similarity.toml";
+
+/// Scores how similar two documents are, from 0 (nothing in common) to 1 (equal).
+pub trait Similarity<D> {
+    /// Returns the similarity between both documents.
+    fn score(&self, a: &D, b: &D) -> f32;
+}
+
+/// A document as printed by the commands ranking documents by similarity.
+#[derive(Serialize)]
+pub struct SimilarityRecord {
+    /// The id of the document.
+    pub id: PathBuf,
+    /// The similarity between the document and the reference one, from 0 to 1.
+    pub similarity: f32,
+}
+
+/// Scores documents by the edit distance between their file names, ignoring case.
+pub struct NameSimilarity;
+
+impl<D> Similarity<D> for NameSimilarity
+where
+    D: Identify<Id = PathBuf>,
+{
+    fn score(&self, a: &D, b: &D) -> f32 {
+        let name = |document: &D| {
+            document
+                .id()
+                .file_name()
+                .map(|name| name.to_string_lossy().to_lowercase())
+                .unwrap_or_default()
+        };
+
+        let (a, b) = (name(a), name(b));
+        let len = a.chars().count().max(b.chars().count());
+        if len == 0 {
+            return 1.;
+        }
+
+        1. - levenshtein(&a, &b) as f32 / len as f32
+    }
+}
+
+/// Scores documents by the amount of links they have in common.
+pub struct LinkSimilarity;
+
+impl<D> Similarity<D> for LinkSimilarity
+where
+    D: TryDeref<Target = Document>,
+{
+    fn score(&self, a: &D, b: &D) -> f32 {
+        let links = |document: &D| -> BTreeSet<PathBuf> {
+            document
+                .try_deref()
+                .into_iter()
+                .flat_map(Document::links)
+                .collect()
+        };
+
+        jaccard(&links(a), &links(b))
+    }
+}
+
+/// Scores documents by the amount of words they have in common, ignoring case.
+pub struct ContentSimilarity;
+
+impl<D> Similarity<D> for ContentSimilarity
+where
+    D: TryDeref<Target = Document>,
+{
+    fn score(&self, a: &D, b: &D) -> f32 {
+        let words = |document: &D| -> BTreeSet<String> {
+            document
+                .try_deref()
+                .map(|document| {
+                    String::from_utf8_lossy(&document.bytes)
+                        .split(|c: char| !c.is_alphanumeric())
+                        .filter(|word| !word.is_empty())
+                        .map(str::to_lowercase)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        jaccard(&words(a), &words(b))
+    }
+}
+
+/// The weight of each built-in scorer, as configured in the [`SIMILARITY_FILE`] of a context.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimilarityWeights {
+    /// The weight of the [`NameSimilarity`].
+    pub name: f32,
+    /// The weight of the [`LinkSimilarity`].
+    pub links: f32,
+    /// The weight of the [`ContentSimilarity`].
+    pub content: f32,
+}
+
+impl Default for SimilarityWeights {
+    fn default() -> Self {
+        Self {
+            name: 1.,
+            links: 1.,
+            content: 1.,
+        }
+    }
+}
+
+impl SimilarityWeights {
+    /// Reads the weights in the given file, if it exists, or returns the default ones otherwise.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| anyhow::Error::msg(format!("parsing {}: {err}", path.display())))
+    }
+}
+
+/// Scores documents as the weighted average of a set of scorers.
+pub struct WeightedSimilarity<D> {
+    scorers: Vec<(f32, Box<dyn Similarity<D>>)>,
+}
+
+impl<D> Default for WeightedSimilarity<D> {
+    fn default() -> Self {
+        Self {
+            scorers: Default::default(),
+        }
+    }
+}
+
+impl<D> From<SimilarityWeights> for WeightedSimilarity<D>
+where
+    D: Identify<Id = PathBuf> + TryDeref<Target = Document>,
+{
+    fn from(weights: SimilarityWeights) -> Self {
+        Self::default()
+            .with_scorer(weights.name, NameSimilarity)
+            .with_scorer(weights.links, LinkSimilarity)
+            .with_scorer(weights.content, ContentSimilarity)
+    }
+}
+
+impl<D> WeightedSimilarity<D> {
+    /// Adds the given scorer with the given weight, ignoring it if the weight is not positive.
+    pub fn with_scorer(mut self, weight: f32, scorer: impl Similarity<D> + 'static) -> Self {
+        if weight > 0. {
+            self.scorers.push((weight, Box::new(scorer)));
+        }
+
+        self
+    }
+}
+
+impl<D> Similarity<D> for WeightedSimilarity<D> {
+    fn score(&self, a: &D, b: &D) -> f32 {
+        let total: f32 = self.scorers.iter().map(|(weight, _)| weight).sum();
+        if total == 0. {
+            return 0.;
+        }
+
+        self.scorers
+            .iter()
+            .map(|(weight, scorer)| weight * scorer.score(a, b))
+            .sum::<f32>()
+            / total
+    }
+}
+
+/// Returns the minimum amount of single-character edits turning one string into the other.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut distances: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut previous = distances[0];
+        distances[0] = i + 1;
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous + usize::from(a != *b);
+            previous = distances[j + 1];
+            distances[j + 1] = substitution.min(distances[j] + 1).min(previous + 1);
+        }
+    }
+
+    distances[b.len()]
+}
+
+/// Returns the size of the intersection of both sets over the size of their union, or zero if
+/// both are empty.
+fn jaccard<T: Ord>(a: &BTreeSet<T>, b: &BTreeSet<T>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.;
+    }
+
+    a.intersection(b).count() as f32 / union as f32
+}
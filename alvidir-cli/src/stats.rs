@@ -0,0 +1,137 @@
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+use alvidir::{
+    deref::TryDeref,
+    document::{lazy::LazyDocument, DocumentRepository},
+    graph::Source,
+    id::Identify,
+    schema::Schema,
+};
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    color::{paint, Role},
+    document::{Document, DocumentLink},
+    output::OutputFormat,
+    repository::LocalDocumentRepository,
+};
+
+/// The units a size in bytes is displayed with, each one 1024 times the previous one.
+const SIZE_UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+/// The summary of the documents as printed by the stats command.
+#[derive(Serialize)]
+struct StatsRecord {
+    /// The amount of documents in the repository.
+    documents: usize,
+    /// The total size, in bytes, of all the documents in the repository.
+    bytes: u64,
+    /// The amount of documents in the trash.
+    trashed: usize,
+    /// The amount of links between documents.
+    links: usize,
+    /// The amount of links pointing to missing documents.
+    dangling: usize,
+    /// The id of the document with the most links pointing to it, if any.
+    most_linked: Option<PathBuf>,
+    /// The amount of links pointing to the most linked document.
+    most_linked_count: usize,
+}
+
+/// Summarize the documents in the context.
+#[derive(Args)]
+pub struct StatsCommand;
+
+pub struct StatsCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub document_repo: Arc<LocalDocumentRepository>,
+    /// The format to print results in.
+    pub output: OutputFormat,
+}
+
+impl<DocumentRepo> StatsCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    pub fn execute(&self, _: StatsCommand) -> Result<()> {
+        let repository = self.document_repo.stats();
+        let graph = self.schema.read();
+
+        let mut links = 0;
+        let mut incoming = BTreeMap::<PathBuf, usize>::new();
+        for document in graph
+            .into_iter()
+            .filter_map(|document| document.try_deref())
+        {
+            for link in document.links().filter(|link| link != document.id()) {
+                links += 1;
+                *incoming.entry(link).or_default() += 1;
+            }
+        }
+
+        let dangling = graph.dangling::<DocumentLink>();
+        let most_linked = incoming
+            .into_iter()
+            .filter(|(id, _)| graph.contains(id))
+            .max_by(|(a_id, a_count), (b_id, b_count)| {
+                a_count.cmp(b_count).then_with(|| b_id.cmp(a_id))
+            });
+
+        let record = StatsRecord {
+            documents: repository.documents,
+            bytes: repository.bytes,
+            trashed: repository.trashed,
+            links,
+            dangling: dangling.len(),
+            most_linked_count: most_linked
+                .as_ref()
+                .map(|(_, count)| *count)
+                .unwrap_or_default(),
+            most_linked: most_linked.map(|(id, _)| id),
+        };
+
+        self.output.print(record, |stdout, stats| {
+            writeln!(stdout, "documents:   {}", stats.documents)?;
+            writeln!(stdout, "size:        {}", human_size(stats.bytes))?;
+            writeln!(stdout, "trashed:     {}", stats.trashed)?;
+            let dangling = format!("{} dangling", stats.dangling);
+            let dangling = match stats.dangling {
+                0 => dangling,
+                _ => paint(Role::Warning, dangling),
+            };
+
+            writeln!(stdout, "links:       {} ({dangling})", stats.links)?;
+            if let Some(most_linked) = &stats.most_linked {
+                writeln!(
+                    stdout,
+                    "most linked: {} ({} links)",
+                    paint(Role::Id, most_linked.display()),
+                    stats.most_linked_count
+                )?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Returns the given amount of bytes in the largest unit that keeps it above one.
+pub fn human_size(bytes: u64) -> String {
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024. && unit < SIZE_UNITS.len() - 1 {
+        size /= 1024.;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        return format!("{bytes} {}", SIZE_UNITS[unit]);
+    }
+
+    format!("{size:.1} {}", SIZE_UNITS[unit])
+}
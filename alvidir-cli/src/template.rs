@@ -0,0 +1,181 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use alvidir::{
+    deref::TryDeref,
+    document::{DocumentRepository, DocumentStore},
+    id::Identify,
+};
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use minijinja::{context, Environment};
+use serde::Serialize;
+
+use crate::{
+    color::{paint, Role},
+    document::Document,
+    dryrun::WriteMode,
+    error::CliError,
+    output::OutputFormat,
+    pager,
+    repository::LocalDocumentRepository,
+};
+
+/// The name under which the user-defined template is registered.
+const TEMPLATE_NAME: &str = "format";
+
+/// The directory, relative to the context, in which the templates of new documents are kept.
+pub const TEMPLATES_DIR: &str = ".alvidir/templates";
+
+/// A user-defined template to render documents with (e.g. `{{id}} {{name}}`).
+///
+/// The available variables are:
+/// - `id`: the id of the document.
+/// - `name`: the file name of the document.
+/// - `content`: the content of the document, only loaded if the template uses it.
+pub struct DocumentTemplate {
+    env: Environment<'static>,
+    /// Whether the template requires the content of the document.
+    loads_content: bool,
+}
+
+impl DocumentTemplate {
+    /// Compiles the given source into a [`DocumentTemplate`].
+    pub fn new(source: impl Into<String>) -> Result<Self> {
+        let mut env = Environment::new();
+        env.add_template_owned(TEMPLATE_NAME, source.into())?;
+
+        let loads_content = env
+            .get_template(TEMPLATE_NAME)?
+            .undeclared_variables(false)
+            .contains("content");
+
+        Ok(Self { env, loads_content })
+    }
+
+    /// Renders the given document.
+    pub fn render<D>(&self, document: &D) -> Result<String>
+    where
+        D: Identify<Id = PathBuf> + TryDeref<Target = Document>,
+    {
+        let id = document.id();
+        let content = self
+            .loads_content
+            .then(|| document.try_deref())
+            .flatten()
+            .map(|document| String::from_utf8_lossy(&document.bytes).into_owned());
+
+        let rendered = self.env.get_template(TEMPLATE_NAME)?.render(context! {
+            id => id.to_string_lossy(),
+            name => id.file_name().map(|name| name.to_string_lossy()),
+            content => content,
+        })?;
+
+        Ok(rendered)
+    }
+}
+
+/// Returns the template with the given name in the given repository.
+pub fn find_template(template_repo: &LocalDocumentRepository, name: PathBuf) -> Result<Document> {
+    // Missing templates are not worth logging as missing documents.
+    template_repo
+        .contains(&name)
+        .then(|| template_repo.find_by_id(&name))
+        .flatten()
+        .ok_or_else(|| CliError::TemplateNotFound(name).into())
+}
+
+/// Renders the given template as the initial content of the document with the given id.
+///
+/// The available variables are the `id` and `name` of the document, as in [`DocumentTemplate`].
+pub fn scaffold(template: &Document, id: &Path) -> Result<String> {
+    let rendered = Environment::new().render_str(
+        &String::from_utf8_lossy(&template.bytes),
+        context! {
+            id => id.to_string_lossy(),
+            name => id.file_name().map(|name| name.to_string_lossy()),
+        },
+    )?;
+
+    Ok(rendered)
+}
+
+/// A template as printed by the listing command.
+#[derive(Serialize)]
+struct TemplateRecord {
+    name: PathBuf,
+}
+
+#[derive(Subcommand)]
+enum TemplateSubCommand {
+    /// List all templates.
+    #[command(alias("ls"))]
+    List,
+    /// Print the content of a template.
+    Show {
+        /// The name of the template.
+        name: PathBuf,
+    },
+    /// Save a template, overwriting any previous one with the same name.
+    Save {
+        /// The name of the template (e.g. 'villain').
+        name: PathBuf,
+        /// The content of the template, where '{{id}}' and '{{name}}' stand for the ones of the
+        /// new document.
+        content: Option<String>,
+    },
+}
+
+/// Manage the templates new documents can be saved from (see 'doc save --template').
+#[derive(Args)]
+pub struct TemplateCommand {
+    #[command(subcommand)]
+    subcommand: TemplateSubCommand,
+}
+
+pub struct TemplateCli {
+    pub template_repo: Arc<LocalDocumentRepository>,
+    /// The format to print results in.
+    pub output: OutputFormat,
+    /// Whether templates may be saved.
+    pub mode: WriteMode,
+}
+
+impl TemplateCli {
+    pub fn execute(&self, command: TemplateCommand) -> Result<()> {
+        match command.subcommand {
+            TemplateSubCommand::List => {
+                let mut names: Vec<_> = self.template_repo.ids().collect();
+                names.sort();
+
+                let records = names.into_iter().map(|name| TemplateRecord { name });
+                self.output.print_all(records, |stdout, record| {
+                    writeln!(stdout, "{}", paint(Role::Id, record.name.display()))?;
+                    Ok(())
+                })?;
+            }
+            TemplateSubCommand::Show { name } => {
+                let template = find_template(&self.template_repo, name)?;
+                pager::stdout().write_all(&template.bytes)?;
+            }
+            TemplateSubCommand::Save { name, content } => {
+                if !self
+                    .mode
+                    .allows(format_args!("save template {}", name.display()))?
+                {
+                    return Ok(());
+                }
+
+                self.template_repo.save(&Document {
+                    path: name,
+                    bytes: content.unwrap_or_default().into_bytes(),
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
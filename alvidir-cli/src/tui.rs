@@ -0,0 +1,201 @@
+use std::{
+    io::{self, IsTerminal},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use alvidir::{
+    deref::TryDeref,
+    document::{lazy::LazyDocument, DocumentRepository},
+    graph::Source,
+    id::Identify,
+    schema::Schema,
+};
+use anyhow::Result;
+use clap::Args;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    widgets::{Block, List, ListState, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+
+use crate::document::Document;
+
+/// The keys available while browsing, as shown in the status line.
+const HELP: &str = "↑/k up  ↓/j down  / search  q quit";
+
+/// Browse documents in an interactive terminal interface.
+#[derive(Args)]
+pub struct TuiCommand;
+
+pub struct TuiCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+}
+
+impl<DocumentRepo> TuiCli<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    pub fn execute(&self, _: TuiCommand) -> Result<()> {
+        if !io::stdout().is_terminal() {
+            return Err(anyhow::Error::msg("the standard output must be a terminal"));
+        }
+
+        let mut browser = Browser {
+            schema: &self.schema,
+            ids: Vec::default(),
+            query: String::default(),
+            searching: false,
+            selected: ListState::default(),
+            quit: false,
+        };
+
+        browser.filter();
+
+        let mut terminal = ratatui::try_init()?;
+        let result = browser.run(&mut terminal);
+        ratatui::try_restore()?;
+        result
+    }
+}
+
+/// The state of the interactive interface.
+struct Browser<'a, DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    schema: &'a Schema<LazyDocument<DocumentRepo>>,
+    /// The ids of the documents matching the query.
+    ids: Vec<PathBuf>,
+    /// The text documents are filtered by.
+    query: String,
+    /// Whether the query is being edited.
+    searching: bool,
+    /// The state of the list of documents.
+    selected: ListState,
+    /// Whether the interface must be closed.
+    quit: bool,
+}
+
+impl<DocumentRepo> Browser<'_, DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository<Document = Document>,
+{
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.render(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.on_key(key.code);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_key(&mut self, code: KeyCode) {
+        if self.searching {
+            match code {
+                KeyCode::Esc | KeyCode::Enter => self.searching = false,
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.filter();
+                }
+                KeyCode::Char(c) => {
+                    self.query.push(c);
+                    self.filter();
+                }
+                _ => {}
+            }
+
+            return;
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('/') => self.searching = true,
+            KeyCode::Up | KeyCode::Char('k') => self.selected.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.selected.select_next(),
+            _ => {}
+        }
+    }
+
+    /// Keeps the ids of the documents whose id or content contain the query, ignoring case.
+    fn filter(&mut self) {
+        let query = self.query.to_lowercase();
+        self.ids = self
+            .schema
+            .read()
+            .into_iter()
+            .filter(|document| {
+                query.is_empty()
+                    || document
+                        .id()
+                        .to_string_lossy()
+                        .to_lowercase()
+                        .contains(&query)
+                    || document
+                        .try_deref()
+                        .map(|document| {
+                            String::from_utf8_lossy(&document.bytes)
+                                .to_lowercase()
+                                .contains(&query)
+                        })
+                        .unwrap_or_default()
+            })
+            .map(|document| document.id().clone())
+            .collect();
+
+        self.selected.select((!self.ids.is_empty()).then_some(0));
+    }
+
+    /// Returns the id and content of the selected document, if any.
+    fn selected(&self) -> Option<(&PathBuf, String)> {
+        let id = self.ids.get(self.selected.selected()?)?;
+        let document = self.schema.read().get(id)?;
+        let content = String::from_utf8_lossy(&document.try_deref()?.bytes).into_owned();
+        Some((id, content))
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+        let [list, preview] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Fill(1)]).areas(main);
+
+        let (title, content) = self
+            .selected()
+            .map(|(id, content)| (id.display().to_string(), content))
+            .unwrap_or_default();
+
+        frame.render_widget(
+            Paragraph::new(content)
+                .block(Block::bordered().title(title))
+                .wrap(Wrap { trim: false }),
+            preview,
+        );
+
+        let items = self.ids.iter().map(|id| id.display().to_string());
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(Block::bordered().title(format!("Documents ({})", self.ids.len())))
+                .highlight_style(Style::new().reversed()),
+            list,
+            &mut self.selected,
+        );
+
+        let status_line = if self.searching || !self.query.is_empty() {
+            format!("/{}", self.query)
+        } else {
+            HELP.to_string()
+        };
+
+        frame.render_widget(Paragraph::new(status_line).dim(), status);
+    }
+}
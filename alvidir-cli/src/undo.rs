@@ -0,0 +1,340 @@
+use std::sync::Arc;
+
+use alvidir::{
+    document::{
+        lazy::LazyDocument, DocumentArchive, DocumentRepository, DocumentStore, DocumentTrash,
+    },
+    schema::{
+        ops::{delete::Delete, save::Save},
+        Error, Schema,
+    },
+};
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    color::{paint, Role},
+    document::Document,
+    dryrun::WriteMode,
+    journal::{Change, Journal},
+    logging,
+    output::OutputFormat,
+};
+
+/// A command in the journal as printed by the listing.
+#[derive(Serialize)]
+struct EntryRecord<'a> {
+    /// The moment the command finished at, in RFC 3339 format.
+    at: &'a str,
+    /// The command line arguments.
+    command: &'a str,
+    /// The amount of changes performed by the command.
+    changes: usize,
+}
+
+/// Undo the changes performed by the last commands.
+#[derive(Args)]
+pub struct UndoCommand {
+    /// The amount of commands to undo.
+    #[arg(short = 'n', long, default_value_t = 1)]
+    steps: usize,
+    /// List the commands that can be undone, from the newest to the oldest, instead.
+    #[arg(long)]
+    list: bool,
+}
+
+pub struct UndoCli<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    pub schema: Arc<Schema<LazyDocument<DocumentRepo>>>,
+    pub document_repo: Arc<DocumentRepo>,
+    pub journal: Arc<Journal>,
+    /// The format to print results in.
+    pub output: OutputFormat,
+    /// Whether the undone commands are removed from the journal.
+    pub mode: WriteMode,
+}
+
+impl<DocumentRepo> UndoCli<DocumentRepo>
+where
+    DocumentRepo: 'static
+        + DocumentRepository<Document = Document>
+        + DocumentStore
+        + DocumentTrash
+        + DocumentArchive,
+{
+    pub fn execute(&self, command: UndoCommand) -> Result<()> {
+        let entries = self.journal.entries()?;
+        if command.list {
+            let records = entries.iter().rev().map(|entry| EntryRecord {
+                at: &entry.at,
+                command: &entry.command,
+                changes: entry.changes.len(),
+            });
+
+            return self.output.print_all(records, |stdout, record| {
+                writeln!(
+                    stdout,
+                    "{}  {}  ({} changes)",
+                    paint(Role::Muted, record.at),
+                    record.command,
+                    record.changes
+                )?;
+
+                Ok(())
+            });
+        }
+
+        if entries.len() < command.steps {
+            return Err(anyhow::Error::msg(format!(
+                "only {} commands can be undone",
+                entries.len()
+            )));
+        }
+
+        // Undoing stops at the first change failing to be reverted, so the journal keeps exactly
+        // the changes that were not undone.
+        let mut undone = 0;
+        let mut reverted = 0;
+        let result = entries
+            .into_iter()
+            .rev()
+            .take(command.steps)
+            .try_for_each(|entry| {
+                reverted = 0;
+                entry.changes.into_iter().rev().try_for_each(|change| {
+                    self.revert(change)?;
+                    reverted += 1;
+                    Ok::<_, anyhow::Error>(())
+                })?;
+
+                undone += 1;
+                reverted = 0;
+                Ok::<_, anyhow::Error>(())
+            });
+
+        // Undoing is not undoable itself, otherwise undoing twice would redo the first undo.
+        self.journal.discard();
+        if self
+            .mode
+            .allows(format_args!("remove {undone} commands from the journal"))?
+        {
+            self.journal.truncate(undone)?;
+            self.journal.truncate_last(reverted)?;
+        }
+
+        result
+    }
+
+    /// Performs the inverse of the given change.
+    fn revert(&self, change: Change) -> Result<()> {
+        match change {
+            Change::Save { id, previous: None } | Change::Restore { id } => {
+                // The document may be missing in the graph if it was saved by an id it is not
+                // loaded by, in which case it must be trashed anyway.
+                match Delete::new(id.clone()).execute(self.schema.transaction()) {
+                    Ok(_) | Err(Error::Noop) => self.document_repo.trash(&id)?,
+                    Err(err) => return Err(err.into()),
+                }
+
+                logging::audit("delete", id.display());
+            }
+            Change::Save {
+                id,
+                previous: Some(bytes),
+            } => {
+                let document = Document { path: id, bytes };
+
+                Save::new(LazyDocument::new(
+                    self.document_repo.clone(),
+                    document.clone(),
+                ))
+                .execute(self.schema.transaction())?;
+
+                self.document_repo.save(&document)?;
+                logging::audit("save", document.path.display());
+            }
+            Change::Archive { id } => {
+                self.document_repo.unarchive(&id)?;
+                logging::audit("unarchive", id.display());
+            }
+            Change::Unarchive { id } => {
+                self.document_repo.archive(&id)?;
+                logging::audit("archive", id.display());
+            }
+            Change::Trash { id } => {
+                self.document_repo.restore(&id)?;
+
+                let document = LazyDocument::builder(self.document_repo.clone())(id.clone());
+                Save::new(document).execute(self.schema.transaction())?;
+                logging::audit("restore", id.display());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf, sync::Arc};
+
+    use crate::{
+        dryrun::WriteMode,
+        error::ErrorKind,
+        journal::{Change, Entry, Journal, JOURNAL_FILE},
+        output::OutputFormat,
+        repository::fixtures::local_schema,
+    };
+
+    use super::{UndoCli, UndoCommand};
+
+    #[test]
+    fn undo_stops_at_the_first_failure() {
+        let context = tempfile::tempdir().unwrap();
+        fs::write(context.path().join("a.md"), "a").unwrap();
+        fs::write(context.path().join("b.md"), "new").unwrap();
+
+        let entry = Entry {
+            at: "2026-10-15T00:00:00Z".to_string(),
+            command: "apply".to_string(),
+            changes: vec![
+                Change::Save {
+                    id: PathBuf::from("a"),
+                    previous: None,
+                },
+                // Never trashed, so it cannot be restored.
+                Change::Trash {
+                    id: PathBuf::from("missing"),
+                },
+                Change::Save {
+                    id: PathBuf::from("b"),
+                    previous: Some(b"old".to_vec()),
+                },
+            ],
+        };
+
+        let journal_file = context.path().join(JOURNAL_FILE);
+        fs::create_dir_all(journal_file.parent().unwrap()).unwrap();
+        fs::write(
+            &journal_file,
+            format!("{}\n", serde_json::to_string(&entry).unwrap()),
+        )
+        .unwrap();
+
+        let (document_repo, schema) = local_schema(context.path());
+        let journal = Arc::new(Journal::new(journal_file));
+        let cli = UndoCli {
+            schema,
+            document_repo,
+            journal: journal.clone(),
+            output: OutputFormat::default(),
+            mode: WriteMode::Write,
+        };
+
+        assert!(cli
+            .execute(UndoCommand {
+                steps: 1,
+                list: false,
+            })
+            .is_err());
+
+        // The last change was reverted, but not the ones before the failing one.
+        assert_eq!(
+            fs::read_to_string(context.path().join("b.md")).unwrap(),
+            "old"
+        );
+        assert!(context.path().join("a.md").exists());
+
+        let entries = journal.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(
+            entries[0].changes.as_slice(),
+            [Change::Save { .. }, Change::Trash { .. }]
+        ));
+    }
+
+    #[test]
+    fn undo_respects_write_mode() {
+        struct Test {
+            name: &'static str,
+            mode: WriteMode,
+            read_only: bool,
+            entries: usize,
+        }
+
+        vec![
+            Test {
+                name: "write",
+                mode: WriteMode::Write,
+                read_only: false,
+                entries: 0,
+            },
+            Test {
+                name: "read only",
+                mode: WriteMode::ReadOnly,
+                read_only: true,
+                entries: 1,
+            },
+            Test {
+                name: "dry run",
+                mode: WriteMode::DryRun,
+                read_only: false,
+                entries: 1,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let context = tempfile::tempdir().unwrap();
+            fs::write(context.path().join("a.md"), "new").unwrap();
+
+            let entry = Entry {
+                at: "2026-10-15T00:00:00Z".to_string(),
+                command: "doc a save".to_string(),
+                changes: vec![Change::Save {
+                    id: PathBuf::from("a"),
+                    previous: Some(b"old".to_vec()),
+                }],
+            };
+
+            let journal_file = context.path().join(JOURNAL_FILE);
+            fs::create_dir_all(journal_file.parent().unwrap()).unwrap();
+            fs::write(
+                &journal_file,
+                format!("{}\n", serde_json::to_string(&entry).unwrap()),
+            )
+            .unwrap();
+
+            let (document_repo, schema) = local_schema(context.path());
+            let journal = Arc::new(Journal::new(journal_file));
+            let cli = UndoCli {
+                schema,
+                document_repo,
+                journal: journal.clone(),
+                output: OutputFormat::default(),
+                mode: test.mode,
+            };
+
+            match cli.execute(UndoCommand {
+                steps: 1,
+                list: false,
+            }) {
+                Ok(_) => assert!(!test.read_only, "{}: undo must fail", test.name),
+                Err(err) => assert!(
+                    test.read_only && ErrorKind::of(&err) == ErrorKind::ReadOnly,
+                    "{}: unexpected error {err}",
+                    test.name
+                ),
+            }
+
+            assert_eq!(
+                journal.entries().unwrap().len(),
+                test.entries,
+                "{}",
+                test.name
+            );
+        });
+    }
+}
@@ -0,0 +1,66 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::Arc,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::repository::LocalDocumentRepository;
+
+/// The time in between two checks for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The documents that changed in between two checks.
+#[derive(Debug, Default)]
+pub struct Changes {
+    /// The id of every document that has been created or modified.
+    pub saved: Vec<PathBuf>,
+    /// The id of every document that has been removed.
+    pub removed: Vec<PathBuf>,
+}
+
+/// Detects changes in the documents of a [`LocalDocumentRepository`], whoever makes them, by
+/// polling their modification time.
+pub struct Watcher {
+    document_repo: Arc<LocalDocumentRepository>,
+    /// The modification time of every document as of the last check.
+    modified: BTreeMap<PathBuf, SystemTime>,
+}
+
+impl Watcher {
+    pub fn new(document_repo: Arc<LocalDocumentRepository>) -> Self {
+        let modified = document_repo.modified();
+        Self {
+            document_repo,
+            modified,
+        }
+    }
+
+    /// Blocks until any document changes, returning the changes.
+    pub fn wait(&mut self) -> Changes {
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            let modified = self.document_repo.modified();
+            let changes = Changes {
+                saved: modified
+                    .iter()
+                    .filter(|(id, at)| self.modified.get(*id) != Some(at))
+                    .map(|(id, _)| id.clone())
+                    .collect(),
+                removed: self
+                    .modified
+                    .keys()
+                    .filter(|id| !modified.contains_key(*id))
+                    .cloned()
+                    .collect(),
+            };
+
+            self.modified = modified;
+            if !changes.saved.is_empty() || !changes.removed.is_empty() {
+                return changes;
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Clock definition.
+
+use std::time::SystemTime;
+
+/// Represents a source of the current time.
+pub trait Clock: Send + Sync {
+    /// Returns the current moment.
+    fn now(&self) -> SystemTime;
+}
+
+/// A [`Clock`] telling the system time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures {
+    use std::{
+        sync::Mutex,
+        time::{Duration, SystemTime},
+    };
+
+    use super::Clock;
+
+    /// A [`Clock`] whose time only changes when told to.
+    #[derive(Debug)]
+    pub struct MockClock {
+        now: Mutex<SystemTime>,
+    }
+
+    impl Default for MockClock {
+        fn default() -> Self {
+            Self::new(SystemTime::UNIX_EPOCH)
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> SystemTime {
+            *self.now.lock().unwrap_or_else(|err| err.into_inner())
+        }
+    }
+
+    impl MockClock {
+        pub fn new(now: SystemTime) -> Self {
+            Self {
+                now: Mutex::new(now),
+            }
+        }
+
+        /// Sets the current moment to the given one.
+        pub fn set(&self, now: SystemTime) {
+            *self.now.lock().unwrap_or_else(|err| err.into_inner()) = now;
+        }
+
+        /// Moves the current moment forward by the given duration.
+        pub fn advance(&self, duration: Duration) {
+            let mut now = self.now.lock().unwrap_or_else(|err| err.into_inner());
+            *now += duration;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::{fixtures::MockClock, Clock};
+
+    #[test]
+    fn mock_clock_only_moves_when_told() {
+        let clock = MockClock::default();
+        assert_eq!(clock.now(), SystemTime::UNIX_EPOCH);
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(
+            clock.now(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(10)
+        );
+
+        clock.set(SystemTime::UNIX_EPOCH + Duration::from_secs(5));
+        assert_eq!(clock.now(), SystemTime::UNIX_EPOCH + Duration::from_secs(5));
+    }
+}
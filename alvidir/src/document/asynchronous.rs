@@ -0,0 +1,589 @@
+//! Asynchronous document related definitions.
+
+use std::{
+    fmt::Debug,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{mpsc, Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+};
+
+use crate::{
+    deref::TryDeref,
+    graph::Source,
+    id::Identify,
+    schema::{
+        ops::{delete::Delete, save::Save},
+        Result, Schema,
+    },
+};
+
+use super::{
+    lazy::LazyDocument, DocumentArchive, DocumentRepository, DocumentStore, DocumentTrash,
+};
+
+/// The asynchronous counterpart of [`DocumentRepository`].
+pub trait AsyncDocumentRepository {
+    /// The type of document retrived by the repository.
+    type Document: Identify;
+
+    /// Retrives the document with the given id, if any.
+    fn find_by_id(
+        &self,
+        id: &<Self::Document as Identify>::Id,
+    ) -> impl Future<Output = Option<Self::Document>> + Send;
+}
+
+/// The asynchronous counterpart of [`DocumentStore`].
+pub trait AsyncDocumentStore: AsyncDocumentRepository {
+    /// Persists the given document, overwriting any previous version of it.
+    fn save(&self, document: &Self::Document) -> impl Future<Output = Result<()>> + Send;
+}
+
+/// The asynchronous counterpart of [`DocumentTrash`].
+pub trait AsyncDocumentTrash: AsyncDocumentRepository {
+    /// Moves the document with the given id into the trash.
+    fn trash(
+        &self,
+        id: &<Self::Document as Identify>::Id,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Moves the document with the given id out of the trash.
+    fn restore(
+        &self,
+        id: &<Self::Document as Identify>::Id,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Removes permanently all the documents in the trash.
+    fn purge(&self) -> impl Future<Output = Result<()>> + Send;
+
+    /// Returns the id of all the documents in the trash.
+    fn trashed(&self) -> impl Future<Output = Vec<<Self::Document as Identify>::Id>> + Send;
+}
+
+/// The asynchronous counterpart of [`DocumentArchive`].
+pub trait AsyncDocumentArchive: AsyncDocumentRepository {
+    /// Marks the document with the given id as archived.
+    fn archive(
+        &self,
+        id: &<Self::Document as Identify>::Id,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Marks the document with the given id as no longer archived.
+    fn unarchive(
+        &self,
+        id: &<Self::Document as Identify>::Id,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Returns the id of all the archived documents.
+    fn archived(&self) -> impl Future<Output = Vec<<Self::Document as Identify>::Id>> + Send;
+}
+
+/// A future whose output is computed by a closure running on another thread.
+struct Offload<T> {
+    state: Arc<Mutex<OffloadState<T>>>,
+}
+
+/// The state shared between an [`Offload`] future and its thread.
+struct OffloadState<T> {
+    /// The output of the closure, or the payload it panicked with, once it finished.
+    output: Option<thread::Result<T>>,
+    /// The waker of the last task polling the future.
+    waker: Option<Waker>,
+}
+
+/// Completes an [`Offload`] future with the output of a closure.
+struct Completer<T> {
+    /// The state of the future, until completed.
+    state: Option<Arc<Mutex<OffloadState<T>>>>,
+}
+
+impl<T> Completer<T> {
+    /// Runs the given closure, completing the future with its output.
+    fn run<F>(mut self, f: F)
+    where
+        F: FnOnce() -> T,
+    {
+        let output = panic::catch_unwind(AssertUnwindSafe(f));
+        self.complete(output);
+    }
+
+    fn complete(&mut self, output: thread::Result<T>) {
+        let Some(state) = self.state.take() else {
+            return;
+        };
+
+        let mut state = state.lock().unwrap_or_else(|err| err.into_inner());
+        state.output = Some(output);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for Completer<T> {
+    fn drop(&mut self) {
+        // A closure that is never run, as when the thread meant to run it is gone, must not leave
+        // the future pending forever.
+        self.complete(Err(Box::new(
+            "offloaded closure was dropped before running",
+        )));
+    }
+}
+
+impl<T> Offload<T>
+where
+    T: 'static + Send,
+{
+    /// Returns a pending future along with the completer of its output.
+    fn pending() -> (Self, Completer<T>) {
+        let state = Arc::new(Mutex::new(OffloadState {
+            output: None,
+            waker: None,
+        }));
+
+        let completer = Completer {
+            state: Some(state.clone()),
+        };
+
+        (Self { state }, completer)
+    }
+
+    /// Runs the given closure on a new thread, returning the future of its output.
+    fn new<F>(f: F) -> Self
+    where
+        F: 'static + Send + FnOnce() -> T,
+    {
+        let (offload, completer) = Self::pending();
+        thread::spawn(move || completer.run(f));
+        offload
+    }
+}
+
+impl<T> Future for Offload<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        match state.output.take() {
+            Some(Ok(output)) => Poll::Ready(output),
+            // The panic of the closure is the one of the task awaiting it.
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Implements the asynchronous repository traits for any synchronous repository.
+///
+/// Every call runs on a thread of its own, so blocking repositories, like file-system ones, do
+/// not block the executor awaiting them. Spawning a thread per call is not cheap, though, so
+/// repositories with a native asynchronous implementation are preferred.
+pub struct AsyncAdapter<DocumentRepo> {
+    /// The repository being adapted.
+    document_repo: Arc<DocumentRepo>,
+}
+
+impl<DocumentRepo> AsyncAdapter<DocumentRepo> {
+    pub fn new(document_repo: DocumentRepo) -> Self {
+        Self {
+            document_repo: Arc::new(document_repo),
+        }
+    }
+
+    /// Returns the future of calling the given closure with the adapted repository.
+    fn offload<F, T>(&self, f: F) -> Offload<T>
+    where
+        DocumentRepo: 'static + Send + Sync,
+        F: 'static + Send + FnOnce(&DocumentRepo) -> T,
+        T: 'static + Send,
+    {
+        let document_repo = self.document_repo.clone();
+        Offload::new(move || f(&document_repo))
+    }
+}
+
+impl<DocumentRepo> AsyncDocumentRepository for AsyncAdapter<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository + Send + Sync,
+    DocumentRepo::Document: 'static + Send,
+    <DocumentRepo::Document as Identify>::Id: 'static + Clone + Send,
+{
+    type Document = DocumentRepo::Document;
+
+    fn find_by_id(
+        &self,
+        id: &<Self::Document as Identify>::Id,
+    ) -> impl Future<Output = Option<Self::Document>> + Send {
+        let id = id.clone();
+        self.offload(move |document_repo| document_repo.find_by_id(&id))
+    }
+}
+
+impl<DocumentRepo> AsyncDocumentStore for AsyncAdapter<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentStore + Send + Sync,
+    DocumentRepo::Document: 'static + Clone + Send,
+    <DocumentRepo::Document as Identify>::Id: 'static + Clone + Send,
+{
+    fn save(&self, document: &Self::Document) -> impl Future<Output = Result<()>> + Send {
+        let document = document.clone();
+        self.offload(move |document_repo| document_repo.save(&document))
+    }
+}
+
+impl<DocumentRepo> AsyncDocumentTrash for AsyncAdapter<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentTrash + Send + Sync,
+    DocumentRepo::Document: 'static + Send,
+    <DocumentRepo::Document as Identify>::Id: 'static + Clone + Send,
+{
+    fn trash(
+        &self,
+        id: &<Self::Document as Identify>::Id,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let id = id.clone();
+        self.offload(move |document_repo| document_repo.trash(&id))
+    }
+
+    fn restore(
+        &self,
+        id: &<Self::Document as Identify>::Id,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let id = id.clone();
+        self.offload(move |document_repo| document_repo.restore(&id))
+    }
+
+    fn purge(&self) -> impl Future<Output = Result<()>> + Send {
+        self.offload(|document_repo| document_repo.purge())
+    }
+
+    fn trashed(&self) -> impl Future<Output = Vec<<Self::Document as Identify>::Id>> + Send {
+        self.offload(|document_repo| document_repo.trashed())
+    }
+}
+
+impl<DocumentRepo> AsyncDocumentArchive for AsyncAdapter<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentArchive + Send + Sync,
+    DocumentRepo::Document: 'static + Send,
+    <DocumentRepo::Document as Identify>::Id: 'static + Clone + Send,
+{
+    fn archive(
+        &self,
+        id: &<Self::Document as Identify>::Id,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let id = id.clone();
+        self.offload(move |document_repo| document_repo.archive(&id))
+    }
+
+    fn unarchive(
+        &self,
+        id: &<Self::Document as Identify>::Id,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let id = id.clone();
+        self.offload(move |document_repo| document_repo.unarchive(&id))
+    }
+
+    fn archived(&self) -> impl Future<Output = Vec<<Self::Document as Identify>::Id>> + Send {
+        self.offload(|document_repo| document_repo.archived())
+    }
+}
+
+/// A call to perform with the schema of an [`AsyncDocumentService`].
+type Job<T> = Box<dyn Send + FnOnce(&Schema<T>)>;
+
+/// Reads, saves and deletes documents through a schema, persisting the changes into the
+/// repository once the schema accepts them, without blocking the caller.
+///
+/// A schema is neither [`Send`] nor [`Sync`], so it lives on a thread of its own, which performs
+/// the calls one at a time and in the same order they were made. The thread finishes once the
+/// service is dropped.
+pub struct AsyncDocumentService<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    /// The repository the changes are persisted into.
+    document_repo: Arc<DocumentRepo>,
+    /// The calls pending to be performed by the thread holding the schema.
+    jobs: mpsc::Sender<Job<LazyDocument<DocumentRepo>>>,
+}
+
+impl<DocumentRepo> AsyncDocumentService<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentRepository + Send + Sync,
+{
+    /// Returns a service for the given repository, building its schema with the given closure on
+    /// the thread the schema will live on.
+    pub fn new<F>(document_repo: Arc<DocumentRepo>, schema: F) -> Self
+    where
+        F: 'static + Send + FnOnce() -> Schema<LazyDocument<DocumentRepo>>,
+    {
+        let (jobs, pending) = mpsc::channel::<Job<LazyDocument<DocumentRepo>>>();
+        thread::spawn(move || {
+            let schema = schema();
+            pending.into_iter().for_each(|job| job(&schema));
+        });
+
+        Self {
+            document_repo,
+            jobs,
+        }
+    }
+
+    /// Returns the future of calling the given closure with the schema and the repository.
+    fn run<F, T>(&self, f: F) -> Offload<T>
+    where
+        F: 'static + Send + FnOnce(&Schema<LazyDocument<DocumentRepo>>, &Arc<DocumentRepo>) -> T,
+        T: 'static + Send,
+    {
+        let (offload, completer) = Offload::pending();
+        let document_repo = self.document_repo.clone();
+
+        // If the thread holding the schema is gone, the job is dropped along with its completer.
+        let _ = self.jobs.send(Box::new(move |schema| {
+            completer.run(|| f(schema, &document_repo))
+        }));
+
+        offload
+    }
+}
+
+impl<DocumentRepo> AsyncDocumentService<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentStore + Send + Sync,
+    DocumentRepo::Document: 'static + Debug + Clone + Send,
+    <DocumentRepo::Document as Identify>::Id: 'static + Debug + Ord + Clone + Send,
+{
+    /// Retrives the document with the given id from the schema, if any.
+    pub fn find_by_id(
+        &self,
+        id: &<DocumentRepo::Document as Identify>::Id,
+    ) -> impl Future<Output = Option<DocumentRepo::Document>> + Send {
+        let id = id.clone();
+        self.run(move |schema, _| {
+            schema
+                .read()
+                .get(&id)
+                .and_then(|document| document.try_deref().cloned())
+        })
+    }
+
+    /// Saves the given document into the schema and, if no constraint is violated, persists it
+    /// into the repository.
+    pub fn save(
+        &self,
+        document: &DocumentRepo::Document,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let document = document.clone();
+        self.run(move |schema, document_repo| {
+            Save::new(LazyDocument::new(document_repo.clone(), document.clone()))
+                .execute(schema.transaction())?;
+
+            document_repo.save(&document)
+        })
+    }
+}
+
+impl<DocumentRepo> AsyncDocumentService<DocumentRepo>
+where
+    DocumentRepo: 'static + DocumentTrash + Send + Sync,
+    DocumentRepo::Document: 'static + Clone + Send,
+    <DocumentRepo::Document as Identify>::Id: 'static + Debug + Ord + Clone + Send,
+{
+    /// Deletes the document with the given id from the schema and, if no constraint is violated,
+    /// moves it into the trash of the repository.
+    pub fn delete(
+        &self,
+        id: &<DocumentRepo::Document as Identify>::Id,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let id = id.clone();
+        self.run(move |schema, document_repo| {
+            Delete::new(id.clone()).execute(schema.transaction())?;
+            document_repo.trash(&id)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        pin::pin,
+        sync::{mpsc, Arc, Mutex},
+        task::{Context, Poll, Wake, Waker},
+        thread::{self, Thread},
+    };
+
+    use crate::{
+        deref::With,
+        document::{
+            lazy::LazyDocument, memory::MemoryDocumentRepository, DocumentRepository,
+            DocumentStore, DocumentTrash,
+        },
+        graph::Graph,
+        id::Identify,
+        schema::{
+            ops::save::BeforeSave,
+            transaction::{Ctx, Target},
+            Error, Result, Schema,
+        },
+    };
+
+    use super::{AsyncAdapter, AsyncDocumentRepository, AsyncDocumentService, AsyncDocumentStore};
+
+    /// Wakes a task by unparking the thread blocked on it.
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// Blocks the current thread until the given future is ready.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Document(usize);
+
+    impl Identify for Document {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.0
+        }
+    }
+
+    /// A repository holding a single document and rejecting any save.
+    struct Repository;
+
+    impl DocumentRepository for Repository {
+        type Document = Document;
+
+        fn find_by_id(&self, id: &usize) -> Option<Self::Document> {
+            (*id == 1).then_some(Document(1))
+        }
+    }
+
+    impl DocumentStore for Repository {
+        fn save(&self, _: &Self::Document) -> Result<()> {
+            Err(Error::ReadOnly)
+        }
+    }
+
+    /// A repository blocking every call until told to go on.
+    struct BlockingRepository(Mutex<mpsc::Receiver<()>>);
+
+    impl DocumentRepository for BlockingRepository {
+        type Document = Document;
+
+        fn find_by_id(&self, id: &usize) -> Option<Self::Document> {
+            self.0.lock().unwrap().recv().unwrap();
+            Some(Document(*id))
+        }
+    }
+
+    #[test]
+    fn adapter_must_delegate_into_repository() {
+        let repo = AsyncAdapter::new(Repository);
+
+        assert_eq!(block_on(repo.find_by_id(&1)), Some(Document(1)));
+        assert_eq!(block_on(repo.find_by_id(&2)), None);
+        assert!(matches!(
+            block_on(repo.save(&Document(2))),
+            Err(Error::ReadOnly)
+        ));
+    }
+
+    #[test]
+    fn adapter_must_not_block_the_caller() {
+        let (go_on, blocked) = mpsc::channel();
+        let repo = AsyncAdapter::new(BlockingRepository(Mutex::new(blocked)));
+
+        let mut future = pin!(repo.find_by_id(&1));
+        assert!(future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+            .is_pending());
+
+        go_on.send(()).unwrap();
+        assert_eq!(block_on(future), Some(Document(1)));
+    }
+
+    /// Returns a service for a repository holding the first document, whose schema rejects any
+    /// document with id 0.
+    fn service() -> (
+        Arc<MemoryDocumentRepository<Document>>,
+        AsyncDocumentService<MemoryDocumentRepository<Document>>,
+    ) {
+        type Lazy = LazyDocument<MemoryDocumentRepository<Document>>;
+
+        fn reject_zero(_: Ctx<Lazy>, target: Target<Lazy>) -> Result<()> {
+            match target.with(|document| *document.id() == 0) {
+                Some(true) => Err(Error::custom("document 0 is not allowed")),
+                _ => Ok(()),
+            }
+        }
+
+        let document_repo = Arc::new(MemoryDocumentRepository::from_iter([Document(1)]));
+        let builder = LazyDocument::builder(document_repo.clone());
+        let service = AsyncDocumentService::new(document_repo.clone(), move || {
+            Schema::from(Graph::from_iter([builder(1)])).with_trigger(BeforeSave, reject_zero)
+        });
+
+        (document_repo, service)
+    }
+
+    #[test]
+    fn service_must_persist_accepted_changes() {
+        let (document_repo, service) = service();
+
+        assert_eq!(block_on(service.find_by_id(&1)), Some(Document(1)));
+        assert_eq!(block_on(service.find_by_id(&2)), None);
+
+        block_on(service.save(&Document(2))).unwrap();
+        assert_eq!(block_on(service.find_by_id(&2)), Some(Document(2)));
+        assert_eq!(document_repo.find_by_id(&2), Some(Document(2)));
+
+        block_on(service.delete(&1)).unwrap();
+        assert_eq!(block_on(service.find_by_id(&1)), None);
+        assert_eq!(document_repo.trashed(), vec![1]);
+    }
+
+    #[test]
+    fn service_must_not_persist_rejected_changes() {
+        let (document_repo, service) = service();
+
+        assert!(block_on(service.save(&Document(0))).is_err());
+        assert_eq!(block_on(service.find_by_id(&0)), None);
+        assert_eq!(document_repo.find_by_id(&0), None);
+
+        assert!(matches!(block_on(service.delete(&2)), Err(Error::Noop)));
+        assert!(document_repo.trashed().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "offloaded closure was dropped before running")]
+    fn service_must_not_pend_forever_without_schema() {
+        let document_repo = Arc::new(MemoryDocumentRepository::<Document>::default());
+        let service = AsyncDocumentService::new(document_repo, || panic!("no schema"));
+
+        block_on(service.find_by_id(&1));
+    }
+}
@@ -0,0 +1,353 @@
+//! Laws every document repository implementation must hold.
+//!
+//! Each law runs against a fresh repository, as returned by the given closure, and panics naming
+//! the offending document if the repository violates it. This allows implementors of custom
+//! backends to prove they behave as the [`MemoryDocumentRepository`] does, e.g. by calling
+//! [`check`] from their own tests with a representative set of documents. Being test helpers,
+//! the laws are only available with the `fixtures` feature enabled.
+//!
+//! [`MemoryDocumentRepository`]: super::memory::MemoryDocumentRepository
+
+use std::fmt::Debug;
+
+use crate::id::Identify;
+
+use super::{DocumentArchive, DocumentStore, DocumentTrash};
+
+/// Returns the last version of each of the given documents, as consecutive documents with the same
+/// id stand for successive versions of the same document.
+fn latest<Document>(documents: &[Document]) -> impl Iterator<Item = &Document>
+where
+    Document: Identify,
+    Document::Id: PartialEq,
+{
+    documents
+        .iter()
+        .enumerate()
+        .filter_map(|(index, document)| {
+            let next = documents.get(index + 1);
+            (next.map(Identify::id) != Some(document.id())).then_some(document)
+        })
+}
+
+/// Asserts that every saved document is found by its id.
+pub fn saved_documents_are_found<F, Repo>(repo: F, documents: &[Repo::Document])
+where
+    F: Fn() -> Repo,
+    Repo: DocumentStore,
+    Repo::Document: Debug + PartialEq,
+    <Repo::Document as Identify>::Id: Debug,
+{
+    let repo = repo();
+    for document in documents {
+        repo.save(document)
+            .unwrap_or_else(|err| panic!("saving {document:?} must succeed: {err}"));
+
+        assert_eq!(
+            repo.find_by_id(document.id()).as_ref(),
+            Some(document),
+            "saved document {:?} must be found",
+            document.id()
+        );
+    }
+}
+
+/// Asserts that saving a document twice keeps the last version of it.
+pub fn save_overwrites_documents<F, Repo>(repo: F, documents: &[Repo::Document])
+where
+    F: Fn() -> Repo,
+    Repo: DocumentStore,
+    Repo::Document: Debug + PartialEq,
+    <Repo::Document as Identify>::Id: Debug + PartialEq,
+{
+    let repo = repo();
+    for (previous, document) in documents.iter().zip(documents.iter().skip(1)) {
+        if previous.id() != document.id() {
+            continue;
+        }
+
+        repo.save(previous)
+            .unwrap_or_else(|err| panic!("saving {previous:?} must succeed: {err}"));
+        repo.save(document)
+            .unwrap_or_else(|err| panic!("saving {document:?} must succeed: {err}"));
+
+        assert_eq!(
+            repo.find_by_id(document.id()).as_ref(),
+            Some(document),
+            "document {:?} must be overwritten",
+            document.id()
+        );
+    }
+}
+
+/// Asserts that trashed documents are not found by their id, but listed in the trash.
+pub fn trashed_documents_are_not_found<F, Repo>(repo: F, documents: &[Repo::Document])
+where
+    F: Fn() -> Repo,
+    Repo: DocumentStore + DocumentTrash,
+    Repo::Document: Debug,
+    <Repo::Document as Identify>::Id: Debug + PartialEq,
+{
+    let repo = repo();
+    for document in latest(documents) {
+        repo.save(document)
+            .unwrap_or_else(|err| panic!("saving {document:?} must succeed: {err}"));
+        repo.trash(document.id())
+            .unwrap_or_else(|err| panic!("trashing {:?} must succeed: {err}", document.id()));
+
+        assert!(
+            repo.find_by_id(document.id()).is_none(),
+            "trashed document {:?} must not be found",
+            document.id()
+        );
+
+        assert!(
+            repo.trashed().contains(document.id()),
+            "trashed document {:?} must be in the trash",
+            document.id()
+        );
+    }
+}
+
+/// Asserts that restored documents are found by their id, and no longer listed in the trash.
+pub fn restored_documents_are_found<F, Repo>(repo: F, documents: &[Repo::Document])
+where
+    F: Fn() -> Repo,
+    Repo: DocumentStore + DocumentTrash,
+    Repo::Document: Debug + PartialEq,
+    <Repo::Document as Identify>::Id: Debug + PartialEq,
+{
+    let repo = repo();
+    for document in documents {
+        repo.save(document)
+            .unwrap_or_else(|err| panic!("saving {document:?} must succeed: {err}"));
+        repo.trash(document.id())
+            .unwrap_or_else(|err| panic!("trashing {:?} must succeed: {err}", document.id()));
+        repo.restore(document.id())
+            .unwrap_or_else(|err| panic!("restoring {:?} must succeed: {err}", document.id()));
+
+        assert_eq!(
+            repo.find_by_id(document.id()).as_ref(),
+            Some(document),
+            "restored document {:?} must be found",
+            document.id()
+        );
+
+        assert!(
+            !repo.trashed().contains(document.id()),
+            "restored document {:?} must not be in the trash",
+            document.id()
+        );
+    }
+}
+
+/// Asserts that restoring a document never overwrites another one with the same id.
+pub fn restore_does_not_overwrite<F, Repo>(repo: F, documents: &[Repo::Document])
+where
+    F: Fn() -> Repo,
+    Repo: DocumentStore + DocumentTrash,
+    Repo::Document: Debug,
+    <Repo::Document as Identify>::Id: Debug,
+{
+    let repo = repo();
+    for document in documents {
+        repo.save(document)
+            .unwrap_or_else(|err| panic!("saving {document:?} must succeed: {err}"));
+        repo.trash(document.id())
+            .unwrap_or_else(|err| panic!("trashing {:?} must succeed: {err}", document.id()));
+        repo.save(document)
+            .unwrap_or_else(|err| panic!("saving {document:?} must succeed: {err}"));
+
+        assert!(
+            repo.restore(document.id()).is_err(),
+            "restoring {:?} over an existing document must fail",
+            document.id()
+        );
+
+        repo.purge()
+            .unwrap_or_else(|err| panic!("purging the trash must succeed: {err}"));
+    }
+}
+
+/// Asserts that trashing a document never overwrites another one with the same id in the trash.
+pub fn trash_does_not_overwrite<F, Repo>(repo: F, documents: &[Repo::Document])
+where
+    F: Fn() -> Repo,
+    Repo: DocumentStore + DocumentTrash,
+    Repo::Document: Debug + PartialEq,
+    <Repo::Document as Identify>::Id: Debug,
+{
+    let repo = repo();
+    for document in documents {
+        repo.save(document)
+            .unwrap_or_else(|err| panic!("saving {document:?} must succeed: {err}"));
+        repo.trash(document.id())
+            .unwrap_or_else(|err| panic!("trashing {:?} must succeed: {err}", document.id()));
+        repo.save(document)
+            .unwrap_or_else(|err| panic!("saving {document:?} must succeed: {err}"));
+
+        assert!(
+            repo.trash(document.id()).is_err(),
+            "trashing {:?} over a trashed document must fail",
+            document.id()
+        );
+
+        assert_eq!(
+            repo.find_by_id(document.id()).as_ref(),
+            Some(document),
+            "document {:?} failing to be trashed must be kept",
+            document.id()
+        );
+
+        repo.purge()
+            .unwrap_or_else(|err| panic!("purging the trash must succeed: {err}"));
+    }
+}
+
+/// Asserts that purging the trash removes every document in it.
+pub fn purge_empties_the_trash<F, Repo>(repo: F, documents: &[Repo::Document])
+where
+    F: Fn() -> Repo,
+    Repo: DocumentStore + DocumentTrash,
+    Repo::Document: Debug,
+    <Repo::Document as Identify>::Id: Debug + PartialEq,
+{
+    let repo = repo();
+    for document in latest(documents) {
+        repo.save(document)
+            .unwrap_or_else(|err| panic!("saving {document:?} must succeed: {err}"));
+        repo.trash(document.id())
+            .unwrap_or_else(|err| panic!("trashing {:?} must succeed: {err}", document.id()));
+    }
+
+    repo.purge()
+        .unwrap_or_else(|err| panic!("purging the trash must succeed: {err}"));
+
+    assert!(
+        repo.trashed().is_empty(),
+        "trash must be empty after purging it"
+    );
+}
+
+/// Asserts that archived documents are listed as such until they are unarchived.
+pub fn archived_documents_are_listed<F, Repo>(repo: F, documents: &[Repo::Document])
+where
+    F: Fn() -> Repo,
+    Repo: DocumentStore + DocumentArchive,
+    Repo::Document: Debug,
+    <Repo::Document as Identify>::Id: Debug + PartialEq,
+{
+    let repo = repo();
+    for document in documents {
+        repo.save(document)
+            .unwrap_or_else(|err| panic!("saving {document:?} must succeed: {err}"));
+        repo.archive(document.id())
+            .unwrap_or_else(|err| panic!("archiving {:?} must succeed: {err}", document.id()));
+
+        assert!(
+            repo.archived().contains(document.id()),
+            "archived document {:?} must be listed as archived",
+            document.id()
+        );
+
+        repo.unarchive(document.id())
+            .unwrap_or_else(|err| panic!("unarchiving {:?} must succeed: {err}", document.id()));
+
+        assert!(
+            !repo.archived().contains(document.id()),
+            "unarchived document {:?} must not be listed as archived",
+            document.id()
+        );
+    }
+}
+
+/// Asserts that only existing documents can be archived, and only archived ones unarchived.
+pub fn archive_requires_existing_documents<F, Repo>(repo: F, documents: &[Repo::Document])
+where
+    F: Fn() -> Repo,
+    Repo: DocumentStore + DocumentArchive,
+    Repo::Document: Debug,
+    <Repo::Document as Identify>::Id: Debug + PartialEq,
+{
+    let repo = repo();
+    for document in documents {
+        assert!(
+            repo.find_by_id(document.id()).is_some() || repo.archive(document.id()).is_err(),
+            "archiving missing document {:?} must fail",
+            document.id()
+        );
+
+        repo.save(document)
+            .unwrap_or_else(|err| panic!("saving {document:?} must succeed: {err}"));
+
+        assert!(
+            repo.unarchive(document.id()).is_err(),
+            "unarchiving document {:?} not archived must fail",
+            document.id()
+        );
+    }
+}
+
+/// Asserts all the laws for the repositories returned by the given closure and the given
+/// documents.
+///
+/// Consecutive documents with the same id stand for successive versions of the same document.
+pub fn check<F, Repo>(repo: F, documents: &[Repo::Document])
+where
+    F: Fn() -> Repo,
+    Repo: DocumentStore + DocumentTrash + DocumentArchive,
+    Repo::Document: Debug + PartialEq,
+    <Repo::Document as Identify>::Id: Debug + PartialEq,
+{
+    saved_documents_are_found(&repo, documents);
+    save_overwrites_documents(&repo, documents);
+    trashed_documents_are_not_found(&repo, documents);
+    restored_documents_are_found(&repo, documents);
+    restore_does_not_overwrite(&repo, documents);
+    trash_does_not_overwrite(&repo, documents);
+    purge_empties_the_trash(&repo, documents);
+    archived_documents_are_listed(&repo, documents);
+    archive_requires_existing_documents(&repo, documents);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        document::{memory::MemoryDocumentRepository, readonly::ReadOnlyRepository},
+        id::Identify,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Document(usize, &'static str);
+
+    impl Identify for Document {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.0
+        }
+    }
+
+    fn documents() -> Vec<Document> {
+        vec![
+            Document(1, "a"),
+            Document(2, "b"),
+            Document(2, "c"),
+            Document(3, "d"),
+        ]
+    }
+
+    #[test]
+    fn memory_repository_holds_all_laws() {
+        super::check(MemoryDocumentRepository::default, &documents());
+    }
+
+    #[test]
+    #[should_panic(expected = "must succeed: read-only")]
+    fn read_only_repository_breaks_laws() {
+        super::check(
+            || ReadOnlyRepository::new(MemoryDocumentRepository::default()),
+            &documents(),
+        );
+    }
+}
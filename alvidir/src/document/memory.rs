@@ -0,0 +1,285 @@
+//! In-memory repository representation.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Mutex,
+};
+
+use crate::{
+    id::Identify,
+    schema::{Error, Result},
+};
+
+use super::{DocumentArchive, DocumentRepository, DocumentStore, DocumentTrash};
+
+/// The documents of a [`MemoryDocumentRepository`], by id.
+type Documents<Document> = BTreeMap<<Document as Identify>::Id, Document>;
+
+/// A [`DocumentRepository`] keeping all documents in memory.
+///
+/// Besides being useful for testing, it stands as the reference implementation of the document
+/// repository traits for any other backend.
+pub struct MemoryDocumentRepository<Document>
+where
+    Document: Identify,
+{
+    /// The documents in the repository.
+    documents: Mutex<Documents<Document>>,
+    /// The documents in the trash.
+    trash: Mutex<Documents<Document>>,
+    /// The id of the archived documents.
+    archive: Mutex<BTreeSet<Document::Id>>,
+}
+
+impl<Document> Default for MemoryDocumentRepository<Document>
+where
+    Document: Identify,
+{
+    fn default() -> Self {
+        Self {
+            documents: Default::default(),
+            trash: Default::default(),
+            archive: Default::default(),
+        }
+    }
+}
+
+impl<Document> FromIterator<Document> for MemoryDocumentRepository<Document>
+where
+    Document: Identify,
+    Document::Id: Ord + Clone,
+{
+    fn from_iter<T: IntoIterator<Item = Document>>(iter: T) -> Self {
+        Self {
+            documents: Mutex::new(
+                iter.into_iter()
+                    .map(|document| (document.id().clone(), document))
+                    .collect(),
+            ),
+            trash: Default::default(),
+            archive: Default::default(),
+        }
+    }
+}
+
+impl<Document> DocumentRepository for MemoryDocumentRepository<Document>
+where
+    Document: Identify + Clone,
+    Document::Id: Ord,
+{
+    type Document = Document;
+
+    fn find_by_id(&self, id: &<Self::Document as Identify>::Id) -> Option<Self::Document> {
+        self.documents
+            .lock()
+            .expect("documents lock should not be poisoned")
+            .get(id)
+            .cloned()
+    }
+}
+
+impl<Document> DocumentStore for MemoryDocumentRepository<Document>
+where
+    Document: Identify + Clone,
+    Document::Id: Ord + Clone,
+{
+    fn save(&self, document: &Self::Document) -> Result<()> {
+        self.documents
+            .lock()
+            .expect("documents lock should not be poisoned")
+            .insert(document.id().clone(), document.clone());
+
+        Ok(())
+    }
+}
+
+impl<Document> DocumentTrash for MemoryDocumentRepository<Document>
+where
+    Document: Identify + Clone,
+    Document::Id: Ord + Clone,
+{
+    fn trash(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        let mut trash = self
+            .trash
+            .lock()
+            .expect("trash lock should not be poisoned");
+        if trash.contains_key(id) {
+            return Err(Error::custom("document already in the trash"));
+        }
+
+        let document = self
+            .documents
+            .lock()
+            .expect("documents lock should not be poisoned")
+            .remove(id)
+            .ok_or_else(|| Error::custom("document not found"))?;
+
+        trash.insert(id.clone(), document);
+        Ok(())
+    }
+
+    fn restore(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        let mut documents = self
+            .documents
+            .lock()
+            .expect("documents lock should not be poisoned");
+
+        if documents.contains_key(id) {
+            return Err(Error::custom("document already exists"));
+        }
+
+        let document = self
+            .trash
+            .lock()
+            .expect("trash lock should not be poisoned")
+            .remove(id)
+            .ok_or_else(|| Error::custom("document not found in the trash"))?;
+
+        documents.insert(id.clone(), document);
+        Ok(())
+    }
+
+    fn purge(&self) -> Result<()> {
+        self.trash
+            .lock()
+            .expect("trash lock should not be poisoned")
+            .clear();
+
+        Ok(())
+    }
+
+    fn trashed(&self) -> Vec<<Self::Document as Identify>::Id> {
+        self.trash
+            .lock()
+            .expect("trash lock should not be poisoned")
+            .keys()
+            .cloned()
+            .collect()
+    }
+}
+
+impl<Document> DocumentArchive for MemoryDocumentRepository<Document>
+where
+    Document: Identify + Clone,
+    Document::Id: Ord + Clone,
+{
+    fn archive(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        if !self
+            .documents
+            .lock()
+            .expect("documents lock should not be poisoned")
+            .contains_key(id)
+        {
+            return Err(Error::custom("document not found"));
+        }
+
+        self.archive
+            .lock()
+            .expect("archive lock should not be poisoned")
+            .insert(id.clone());
+
+        Ok(())
+    }
+
+    fn unarchive(&self, id: &<Self::Document as Identify>::Id) -> Result<()> {
+        if !self
+            .archive
+            .lock()
+            .expect("archive lock should not be poisoned")
+            .remove(id)
+        {
+            return Err(Error::custom("document not archived"));
+        }
+
+        Ok(())
+    }
+
+    fn archived(&self) -> Vec<<Self::Document as Identify>::Id> {
+        self.archive
+            .lock()
+            .expect("archive lock should not be poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+impl<Document> MemoryDocumentRepository<Document>
+where
+    Document: Identify,
+    Document::Id: Clone,
+{
+    /// Returns the id of all the documents in the repository, in order.
+    pub fn ids(&self) -> Vec<Document::Id> {
+        self.documents
+            .lock()
+            .expect("documents lock should not be poisoned")
+            .keys()
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        document::{DocumentArchive, DocumentRepository, DocumentStore, DocumentTrash},
+        id::Identify,
+    };
+
+    use super::MemoryDocumentRepository;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Document(usize, &'static str);
+
+    impl Identify for Document {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.0
+        }
+    }
+
+    #[test]
+    fn memory_repository_must_keep_trashed_documents_apart() {
+        let repo = MemoryDocumentRepository::from_iter([Document(1, "a"), Document(2, "b")]);
+
+        repo.save(&Document(2, "c")).unwrap();
+        assert_eq!(repo.find_by_id(&2), Some(Document(2, "c")));
+
+        repo.trash(&1).unwrap();
+        assert_eq!(repo.find_by_id(&1), None);
+        assert_eq!(repo.ids(), vec![2]);
+        assert_eq!(repo.trashed(), vec![1]);
+        assert!(repo.trash(&1).is_err(), "trashing a missing document");
+
+        repo.save(&Document(1, "d")).unwrap();
+        assert!(
+            repo.restore(&1).is_err(),
+            "restoring over an existing document"
+        );
+
+        repo.trash(&2).unwrap();
+        repo.purge().unwrap();
+        assert!(repo.trashed().is_empty());
+        assert!(repo.restore(&2).is_err(), "restoring a purged document");
+    }
+
+    #[test]
+    fn memory_repository_must_keep_archived_documents_found() {
+        let repo = MemoryDocumentRepository::from_iter([Document(1, "a"), Document(2, "b")]);
+
+        repo.archive(&1).unwrap();
+        assert_eq!(repo.find_by_id(&1), Some(Document(1, "a")));
+        assert_eq!(repo.ids(), vec![1, 2]);
+        assert_eq!(repo.archived(), vec![1]);
+        assert!(repo.archive(&3).is_err(), "archiving a missing document");
+
+        repo.unarchive(&1).unwrap();
+        assert!(repo.archived().is_empty());
+        assert!(
+            repo.unarchive(&2).is_err(),
+            "unarchiving a document that is not archived"
+        );
+    }
+}
@@ -1,8 +1,14 @@
 //! Document related definitions.
 
-use crate::id::Identify;
+use crate::{id::Identify, schema::Result};
 
+#[cfg(feature = "async")]
+pub mod asynchronous;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod conformance;
 pub mod lazy;
+pub mod memory;
+pub mod readonly;
 
 /// A repository in charge of document's persistance.
 pub trait DocumentRepository {
@@ -12,3 +18,36 @@ pub trait DocumentRepository {
     /// Retrives the document with the given id, if any.
     fn find_by_id(&self, id: &<Self::Document as Identify>::Id) -> Option<Self::Document>;
 }
+
+/// A repository able to persist documents.
+pub trait DocumentStore: DocumentRepository {
+    /// Persists the given document, overwriting any previous version of it.
+    fn save(&self, document: &Self::Document) -> Result<()>;
+}
+
+/// A repository that keeps deleted documents recoverable.
+pub trait DocumentTrash: DocumentRepository {
+    /// Moves the document with the given id into the trash.
+    fn trash(&self, id: &<Self::Document as Identify>::Id) -> Result<()>;
+
+    /// Moves the document with the given id out of the trash.
+    fn restore(&self, id: &<Self::Document as Identify>::Id) -> Result<()>;
+
+    /// Removes permanently all the documents in the trash.
+    fn purge(&self) -> Result<()>;
+
+    /// Returns the id of all the documents in the trash.
+    fn trashed(&self) -> Vec<<Self::Document as Identify>::Id>;
+}
+
+/// A repository that keeps documents out of sight without removing them.
+pub trait DocumentArchive: DocumentRepository {
+    /// Marks the document with the given id as archived.
+    fn archive(&self, id: &<Self::Document as Identify>::Id) -> Result<()>;
+
+    /// Marks the document with the given id as no longer archived.
+    fn unarchive(&self, id: &<Self::Document as Identify>::Id) -> Result<()>;
+
+    /// Returns the id of all the archived documents.
+    fn archived(&self) -> Vec<<Self::Document as Identify>::Id>;
+}
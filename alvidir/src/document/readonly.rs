@@ -0,0 +1,159 @@
+//! Read-only repository representation.
+
+use crate::{
+    id::Identify,
+    schema::{Error, Result},
+};
+
+use super::{DocumentArchive, DocumentRepository, DocumentStore, DocumentTrash};
+
+/// A [`DocumentRepository`] decorator that rejects any modification with [`Error::ReadOnly`].
+pub struct ReadOnlyRepository<DocumentRepo> {
+    /// The repository being decorated.
+    document_repo: DocumentRepo,
+}
+
+impl<DocumentRepo> ReadOnlyRepository<DocumentRepo> {
+    pub fn new(document_repo: DocumentRepo) -> Self {
+        Self { document_repo }
+    }
+}
+
+impl<DocumentRepo> DocumentRepository for ReadOnlyRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    type Document = DocumentRepo::Document;
+
+    fn find_by_id(&self, id: &<Self::Document as Identify>::Id) -> Option<Self::Document> {
+        self.document_repo.find_by_id(id)
+    }
+}
+
+impl<DocumentRepo> DocumentStore for ReadOnlyRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentRepository,
+{
+    fn save(&self, _: &Self::Document) -> Result<()> {
+        Err(Error::ReadOnly)
+    }
+}
+
+impl<DocumentRepo> DocumentTrash for ReadOnlyRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentTrash,
+{
+    fn trash(&self, _: &<Self::Document as Identify>::Id) -> Result<()> {
+        Err(Error::ReadOnly)
+    }
+
+    fn restore(&self, _: &<Self::Document as Identify>::Id) -> Result<()> {
+        Err(Error::ReadOnly)
+    }
+
+    fn purge(&self) -> Result<()> {
+        Err(Error::ReadOnly)
+    }
+
+    fn trashed(&self) -> Vec<<Self::Document as Identify>::Id> {
+        self.document_repo.trashed()
+    }
+}
+
+impl<DocumentRepo> DocumentArchive for ReadOnlyRepository<DocumentRepo>
+where
+    DocumentRepo: DocumentArchive,
+{
+    fn archive(&self, _: &<Self::Document as Identify>::Id) -> Result<()> {
+        Err(Error::ReadOnly)
+    }
+
+    fn unarchive(&self, _: &<Self::Document as Identify>::Id) -> Result<()> {
+        Err(Error::ReadOnly)
+    }
+
+    fn archived(&self) -> Vec<<Self::Document as Identify>::Id> {
+        self.document_repo.archived()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        document::{DocumentArchive, DocumentRepository, DocumentStore, DocumentTrash},
+        id::Identify,
+        schema::{Error, Result},
+    };
+
+    use super::ReadOnlyRepository;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Document(usize);
+
+    impl Identify for Document {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.0
+        }
+    }
+
+    /// A repository holding a single document, a single trashed one and a single archived one.
+    struct Repository;
+
+    impl DocumentRepository for Repository {
+        type Document = Document;
+
+        fn find_by_id(&self, id: &usize) -> Option<Self::Document> {
+            (*id == 1).then_some(Document(1))
+        }
+    }
+
+    impl DocumentTrash for Repository {
+        fn trash(&self, _: &usize) -> Result<()> {
+            Ok(())
+        }
+
+        fn restore(&self, _: &usize) -> Result<()> {
+            Ok(())
+        }
+
+        fn purge(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn trashed(&self) -> Vec<usize> {
+            vec![2]
+        }
+    }
+
+    impl DocumentArchive for Repository {
+        fn archive(&self, _: &usize) -> Result<()> {
+            Ok(())
+        }
+
+        fn unarchive(&self, _: &usize) -> Result<()> {
+            Ok(())
+        }
+
+        fn archived(&self) -> Vec<usize> {
+            vec![3]
+        }
+    }
+
+    #[test]
+    fn read_only_repository_must_reject_modifications() {
+        let repo = ReadOnlyRepository::new(Repository);
+
+        assert_eq!(repo.find_by_id(&1), Some(Document(1)));
+        assert_eq!(repo.trashed(), vec![2]);
+        assert_eq!(repo.archived(), vec![3]);
+
+        assert!(matches!(repo.save(&Document(3)), Err(Error::ReadOnly)));
+        assert!(matches!(repo.trash(&1), Err(Error::ReadOnly)));
+        assert!(matches!(repo.restore(&2), Err(Error::ReadOnly)));
+        assert!(matches!(repo.purge(), Err(Error::ReadOnly)));
+        assert!(matches!(repo.archive(&1), Err(Error::ReadOnly)));
+        assert!(matches!(repo.unarchive(&3), Err(Error::ReadOnly)));
+    }
+}
@@ -0,0 +1,85 @@
+//! Referential integrity of graphs.
+
+use crate::{id::Identify, property::Property};
+
+use super::Graph;
+
+/// An edge pointing to a node that does not exist in the graph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DanglingEdge<Id> {
+    /// The id of the node the edge starts from.
+    pub from: Id,
+    /// The id of the missing node.
+    pub to: Id,
+}
+
+impl<T> Graph<T>
+where
+    T: Identify,
+    T::Id: Ord + Clone,
+{
+    /// Returns all the edges of the given type pointing to nodes that do not exist in the graph.
+    pub fn dangling<Edge>(&self) -> Vec<DanglingEdge<T::Id>>
+    where
+        Edge: Property<T> + Identify<Id = T::Id>,
+    {
+        self.nodes
+            .values()
+            .flat_map(|node| {
+                Edge::all(node)
+                    .into_iter()
+                    .filter(|edge| !self.nodes.contains_key(edge.id()))
+                    .map(|edge| DanglingEdge {
+                        from: node.id().clone(),
+                        to: edge.id().clone(),
+                    })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{
+        fixtures::{fake_node, FakeEdge, FakeNode},
+        Graph,
+    };
+
+    use super::DanglingEdge;
+
+    #[test]
+    fn dangling_edges_in_graph() {
+        struct Test<'a> {
+            name: &'a str,
+            graph: Graph<FakeNode<'a, i8>>,
+            output: Vec<DanglingEdge<i8>>,
+        }
+
+        vec![
+            Test {
+                name: "empty graph",
+                graph: Graph::default(),
+                output: vec![],
+            },
+            Test {
+                name: "all edges exist",
+                graph: Graph::from_iter(vec![fake_node!(1, 2), fake_node!(2, 1)]),
+                output: vec![],
+            },
+            Test {
+                name: "missing node",
+                graph: Graph::from_iter(vec![fake_node!(1, 2), fake_node!(2, 3)]),
+                output: vec![DanglingEdge { from: 2, to: 3 }],
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            assert_eq!(
+                test.graph.dangling::<FakeEdge<i8>>(),
+                test.output,
+                "{}",
+                test.name
+            );
+        });
+    }
+}
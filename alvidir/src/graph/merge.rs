@@ -0,0 +1,117 @@
+//! Merge of graphs.
+
+use crate::id::Identify;
+
+use super::Graph;
+
+impl<T> Graph<T>
+where
+    T: Identify + PartialEq,
+    T::Id: Ord + Clone,
+{
+    /// Returns the [`Graph`] resulting from combining self with the given one.
+    ///
+    /// Nodes present in only one of the graphs are kept as is. Nodes present in both of them
+    /// whose values differ are in conflict, and get replaced by the output of the given closure,
+    /// which receives our node first and their node second.
+    pub fn merge<F, E>(mut self, theirs: Self, mut resolve: F) -> Result<Self, E>
+    where
+        F: FnMut(T, T) -> Result<T, E>,
+    {
+        for (node_id, their_node) in theirs.nodes {
+            let node = match self.nodes.remove(&node_id) {
+                Some(our_node) if our_node != their_node => resolve(our_node, their_node)?,
+                _ => their_node,
+            };
+
+            self.nodes.insert(node_id, node);
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use crate::{graph::Graph, id::Identify};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Node {
+        id: usize,
+        value: &'static str,
+    }
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    #[test]
+    fn merge_graphs() {
+        struct Test<'a> {
+            name: &'a str,
+            ours: Vec<Node>,
+            theirs: Vec<Node>,
+            conflicts: Vec<usize>,
+            output: Vec<Node>,
+        }
+
+        let node = |id, value| Node { id, value };
+
+        vec![
+            Test {
+                name: "disjoint graphs",
+                ours: vec![node(1, "a")],
+                theirs: vec![node(2, "b")],
+                conflicts: vec![],
+                output: vec![node(1, "a"), node(2, "b")],
+            },
+            Test {
+                name: "equal nodes are not in conflict",
+                ours: vec![node(1, "a")],
+                theirs: vec![node(1, "a")],
+                conflicts: vec![],
+                output: vec![node(1, "a")],
+            },
+            Test {
+                name: "different nodes are resolved",
+                ours: vec![node(1, "a"), node(2, "b")],
+                theirs: vec![node(1, "c"), node(3, "d")],
+                conflicts: vec![1],
+                output: vec![node(1, "a"), node(2, "b"), node(3, "d")],
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let mut conflicts = Vec::default();
+            let output = Graph::from_iter(test.ours)
+                .merge(Graph::from_iter(test.theirs), |ours, _| {
+                    conflicts.push(ours.id);
+                    Ok::<_, Infallible>(ours)
+                })
+                .unwrap();
+
+            assert_eq!(conflicts, test.conflicts, "{}", test.name);
+            assert_eq!(
+                output.into_iter().cloned().collect::<Vec<_>>(),
+                test.output,
+                "{}",
+                test.name
+            );
+        });
+    }
+
+    #[test]
+    fn merge_fails_if_resolution_does() {
+        let ours = Graph::default().with_node(Node { id: 1, value: "a" });
+        let theirs = Graph::default().with_node(Node { id: 1, value: "b" });
+
+        let result = ours.merge(theirs, |_, _| Err("aborted"));
+        assert_eq!(result.err(), Some("aborted"));
+    }
+}
@@ -4,7 +4,10 @@ use std::collections::{btree_map::Values, BTreeMap};
 
 use crate::id::Identify;
 
+mod integrity;
+mod merge;
 mod proxy;
+pub use integrity::DanglingEdge;
 pub use proxy::*;
 
 /// An arbitrary graph.
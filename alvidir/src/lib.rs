@@ -1,3 +1,4 @@
+pub mod clock;
 pub mod deref;
 pub mod document;
 pub mod graph;
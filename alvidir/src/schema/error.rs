@@ -1,6 +1,6 @@
 //! Error definition.
 
-use std::fmt::Display;
+use std::{fmt::Display, io};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -9,6 +9,12 @@ pub enum Error {
     /// Determines that an operation has no effect.
     #[error("nothing to apply")]
     Noop,
+    /// Determines that an operation would modify a read-only resource.
+    #[error("read-only access")]
+    ReadOnly,
+    /// Determines that reading or writing the underlying storage failed.
+    #[error(transparent)]
+    Io(#[from] io::Error),
     #[error("{0}")]
     Msg(String),
 }
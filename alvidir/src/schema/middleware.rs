@@ -0,0 +1,305 @@
+//! Middleware definition.
+
+use std::time::Instant;
+
+use crate::id::Identify;
+
+use super::{transaction::Context, Result};
+
+/// The rest of the layers wrapped by a [`Middleware`], the transaction itself included.
+pub struct Next<'a> {
+    f: &'a mut dyn FnMut() -> Result<()>,
+}
+
+impl Next<'_> {
+    /// Proceeds with the transaction.
+    pub fn run(self) -> Result<()> {
+        (self.f)()
+    }
+}
+
+/// Represents a layer wrapping the execution of every transaction in a schema.
+///
+/// Middlewares are called in the same order they were added into the schema, the first one being
+/// the outermost. Not calling the next layer aborts the transaction.
+pub trait Middleware<T>
+where
+    T: Identify,
+{
+    /// Handles the transaction in the given context.
+    fn handle(&self, ctx: &Context<'_, T>, next: Next<'_>) -> Result<()>;
+
+    /// Observes the result of every trigger executed during the transaction, by name.
+    fn on_trigger(&self, _trigger: &str, _result: &Result<()>) {}
+}
+
+impl<T, F> Middleware<T> for F
+where
+    T: Identify,
+    F: Fn(&Context<'_, T>, Next<'_>) -> Result<()>,
+{
+    fn handle(&self, ctx: &Context<'_, T>, next: Next<'_>) -> Result<()> {
+        (self)(ctx, next)
+    }
+}
+
+/// A sorted set of arbitrary middlewares.
+pub struct MiddlewareSet<T> {
+    middlewares: Vec<Box<dyn Middleware<T>>>,
+}
+
+impl<T> Default for MiddlewareSet<T> {
+    fn default() -> Self {
+        Self {
+            middlewares: Default::default(),
+        }
+    }
+}
+
+impl<T> MiddlewareSet<T>
+where
+    T: Identify,
+{
+    /// Adds a new middleware as the innermost one.
+    pub fn with_middleware(mut self, middleware: impl Middleware<T> + 'static) -> Self {
+        self.middlewares.push(Box::new(middleware));
+        self
+    }
+
+    /// Handles the transaction in the given context through all the middlewares, calling the
+    /// given closure as the innermost layer.
+    pub fn handle(&self, ctx: &Context<'_, T>, f: &mut dyn FnMut() -> Result<()>) -> Result<()> {
+        fn chain<T>(
+            middlewares: &[Box<dyn Middleware<T>>],
+            ctx: &Context<'_, T>,
+            f: &mut dyn FnMut() -> Result<()>,
+        ) -> Result<()>
+        where
+            T: Identify,
+        {
+            let Some((middleware, rest)) = middlewares.split_first() else {
+                return f();
+            };
+
+            middleware.handle(
+                ctx,
+                Next {
+                    f: &mut || chain(rest, ctx, f),
+                },
+            )
+        }
+
+        chain(&self.middlewares, ctx, f)
+    }
+
+    /// Notifies all the middlewares about the result of the given trigger.
+    pub fn observe(&self, trigger: &str, result: &Result<()>) {
+        self.middlewares
+            .iter()
+            .for_each(|middleware| middleware.on_trigger(trigger, result));
+    }
+}
+
+/// A [`Middleware`] that runs every transaction, triggers included, but discards all its
+/// operations instead of committing them.
+pub struct DryRun;
+
+impl<T> Middleware<T> for DryRun
+where
+    T: Identify,
+{
+    fn handle(&self, ctx: &Context<'_, T>, next: Next<'_>) -> Result<()> {
+        next.run()?;
+        ctx.discard();
+        Ok(())
+    }
+}
+
+/// A [`Middleware`] that logs how long every transaction takes.
+pub struct Timing;
+
+impl<T> Middleware<T> for Timing
+where
+    T: Identify,
+{
+    fn handle(&self, _: &Context<'_, T>, next: Next<'_>) -> Result<()> {
+        let start = Instant::now();
+        let result = next.run();
+
+        tracing::debug!(
+            elapsed = ?start.elapsed(),
+            success = result.is_ok(),
+            "transaction"
+        );
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        graph::{
+            fixtures::{fake_node, FakeNode},
+            Graph, Source,
+        },
+        schema::{
+            ops::save::{BeforeSave, Save},
+            transaction::{Context, Ctx, Transaction},
+            Error, Result, Schema,
+        },
+    };
+
+    use super::{DryRun, Middleware, Next};
+
+    #[test]
+    fn middlewares_wrap_transactions_in_order() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let layer = |name: &'static str| {
+            let calls = calls.clone();
+            move |_: &Context<'_, FakeNode<'static, usize>>, next: Next<'_>| {
+                calls.lock().unwrap().push(format!("{name} in"));
+                let result = next.run();
+                calls.lock().unwrap().push(format!("{name} out"));
+                result
+            }
+        };
+
+        let schema = Schema::from(Graph::default())
+            .with_middleware(layer("outer"))
+            .with_middleware(layer("inner"));
+
+        Save::new(fake_node!(1))
+            .execute(schema.transaction())
+            .expect("save should succeed");
+
+        assert!(schema.read().contains(&1));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["outer in", "inner in", "inner out", "outer out"]
+        );
+    }
+
+    #[test]
+    fn middleware_may_abort_transactions() {
+        struct Test<'a> {
+            name: &'a str,
+            schema: Schema<FakeNode<'static, usize>>,
+            must_fail: bool,
+        }
+
+        vec![
+            Test {
+                name: "failing middleware",
+                schema: Schema::from(Graph::default()).with_middleware(
+                    |_: &Context<'_, FakeNode<'static, usize>>, _: Next<'_>| -> Result<()> {
+                        Err(Error::custom("unauthorized"))
+                    },
+                ),
+                must_fail: true,
+            },
+            Test {
+                name: "skipping middleware",
+                schema: Schema::from(Graph::default()).with_middleware(
+                    |_: &Context<'_, FakeNode<'static, usize>>, _: Next<'_>| -> Result<()> {
+                        Ok(())
+                    },
+                ),
+                must_fail: true,
+            },
+            Test {
+                name: "dry run",
+                schema: Schema::from(Graph::default()).with_middleware(DryRun),
+                must_fail: false,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let result = Save::new(fake_node!(1)).execute(test.schema.transaction());
+            assert_eq!(result.is_err(), test.must_fail, "{}", test.name);
+            assert!(
+                !test.schema.read().contains(&1),
+                "{}: aborted transaction must not apply changes",
+                test.name
+            );
+        });
+    }
+
+    #[test]
+    fn middlewares_do_not_wrap_nested_transactions() {
+        let calls = Arc::new(Mutex::new(0));
+        let schema = Schema::from(Graph::default()).with_middleware({
+            let calls = calls.clone();
+            move |_: &Context<'_, FakeNode<'static, usize>>, next: Next<'_>| {
+                *calls.lock().unwrap() += 1;
+                next.run()
+            }
+        });
+
+        schema
+            .transaction()
+            .with(|ctx| {
+                Save::new(fake_node!(1)).execute(ctx.transaction())?;
+                Save::new(fake_node!(2)).execute(ctx.transaction())
+            })
+            .expect("transaction should succeed");
+
+        assert_eq!(*calls.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn middlewares_observe_every_trigger() {
+        type Node = FakeNode<'static, usize>;
+
+        #[derive(Default)]
+        struct Observer(Mutex<Vec<(String, bool)>>);
+
+        impl Middleware<Node> for Arc<Observer> {
+            fn handle(&self, _: &Context<'_, Node>, next: Next<'_>) -> Result<()> {
+                next.run()
+            }
+
+            fn on_trigger(&self, trigger: &str, result: &Result<()>) {
+                let name = trigger.rsplit("::").next().unwrap_or_default();
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((name.to_string(), result.is_ok()));
+            }
+        }
+
+        fn passing(_: Ctx<Node>) -> Result<()> {
+            Ok(())
+        }
+
+        fn failing(_: Ctx<Node>) -> Result<()> {
+            Err(Error::custom("constraint violated"))
+        }
+
+        fn unreachable(_: Ctx<Node>) -> Result<()> {
+            Ok(())
+        }
+
+        let observer = Arc::new(Observer::default());
+        let schema = Schema::from(Graph::default())
+            .with_middleware(observer.clone())
+            .with_trigger(BeforeSave, passing)
+            .with_trigger(BeforeSave, failing)
+            .with_trigger(BeforeSave, unreachable);
+
+        let result = Save::new(fake_node!(1)).execute(schema.transaction());
+        assert!(
+            result.is_err(),
+            "failing trigger must abort the transaction"
+        );
+        assert_eq!(
+            *observer.0.lock().unwrap(),
+            vec![
+                ("passing".to_string(), true),
+                ("failing".to_string(), false)
+            ]
+        );
+    }
+}
@@ -3,15 +3,18 @@
 mod error;
 pub use error::{Error, Result};
 pub mod guard;
+pub mod middleware;
 pub mod ops;
 pub mod plugin;
 pub mod resource;
+pub mod schedule;
 pub mod transaction;
 pub mod trigger;
 
 use std::sync::RwLock;
 
 use guard::{SchemaReadGuard, SchemaWriteGuard};
+use middleware::{Middleware, MiddlewareSet};
 use plugin::Plugin;
 use resource::ResourceSet;
 use transaction::Background;
@@ -30,6 +33,8 @@ where
     resources: ResourceSet,
     /// All the triggers in the schema.
     triggers: TriggerSet<T>,
+    /// All the middlewares wrapping the transactions in the schema.
+    middlewares: MiddlewareSet<T>,
 }
 
 impl<T> From<Graph<T>> for Schema<T>
@@ -41,6 +46,7 @@ where
             graph: RwLock::new(graph),
             resources: Default::default(),
             triggers: Default::default(),
+            middlewares: Default::default(),
         }
     }
 }
@@ -83,6 +89,14 @@ where
         self
     }
 
+    /// Wraps every transaction in this schema with the given middleware.
+    ///
+    /// Middlewares are called in the same order they are added, the first one being the outermost.
+    pub fn with_middleware(mut self, middleware: impl Middleware<T> + 'static) -> Self {
+        self.middlewares = self.middlewares.with_middleware(middleware);
+        self
+    }
+
     /// Returns the resource set of this schema.
     pub fn resources(&self) -> &ResourceSet {
         &self.resources
@@ -93,6 +107,11 @@ where
         &self.triggers
     }
 
+    /// Returns the middleware set of this schema.
+    pub fn middlewares(&self) -> &MiddlewareSet<T> {
+        &self.middlewares
+    }
+
     /// Returns a new transaction background.
     #[inline]
     pub fn transaction(&self) -> Background<'_, T> {
@@ -0,0 +1,274 @@
+//! Scheduler definition.
+
+use std::time::{Duration, SystemTime};
+
+use crate::{clock::Clock, id::Identify};
+
+use super::{Result, Schema};
+
+/// Determines when a job must run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// Once, as soon as the given moment is reached.
+    At(SystemTime),
+    /// Periodically, the first time as soon as possible.
+    Every(Duration),
+}
+
+/// The record of a single run of a job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Execution {
+    /// The name of the job.
+    pub job: String,
+    /// The moment the job was run at.
+    pub at: SystemTime,
+    /// The error the job failed with, if any.
+    pub error: Option<String>,
+}
+
+/// A closure to run against a schema.
+type JobFn<T> = Box<dyn Fn(&Schema<T>) -> Result<()>>;
+
+/// A named closure to run against a schema.
+struct Job<T>
+where
+    T: Identify,
+{
+    name: String,
+    schedule: Schedule,
+    /// The moment the job is due at, or none if it must not run anymore.
+    next: Option<SystemTime>,
+    f: JobFn<T>,
+}
+
+impl<T> Job<T>
+where
+    T: Identify,
+{
+    /// Returns the moment the job is due at if it was last run at the given one.
+    fn next_after(&self, last_run: SystemTime) -> Option<SystemTime> {
+        match self.schedule {
+            Schedule::At(at) => (last_run < at).then_some(at),
+            Schedule::Every(period) => Some(last_run + period),
+        }
+    }
+}
+
+/// Runs jobs against a schema as they become due, keeping the history of their executions.
+///
+/// The scheduler does not hold any thread of its own: callers are expected to call
+/// [`Scheduler::tick`] periodically with the [`Clock`] telling the current time, e.g. sleeping
+/// until [`Scheduler::next_run`] in between.
+/// Persisting the history allows restoring the schedule of each job afterwards through
+/// [`Scheduler::with_history`].
+pub struct Scheduler<T>
+where
+    T: Identify,
+{
+    jobs: Vec<Job<T>>,
+    history: Vec<Execution>,
+}
+
+impl<T> Default for Scheduler<T>
+where
+    T: Identify,
+{
+    fn default() -> Self {
+        Self {
+            jobs: Default::default(),
+            history: Default::default(),
+        }
+    }
+}
+
+impl<T> Scheduler<T>
+where
+    T: Identify,
+{
+    /// Registers the given closure as a job with the given name and schedule.
+    pub fn with_job<F>(mut self, name: impl Into<String>, schedule: Schedule, f: F) -> Self
+    where
+        F: Fn(&Schema<T>) -> Result<()> + 'static,
+    {
+        let next = match schedule {
+            Schedule::At(at) => at,
+            Schedule::Every(_) => SystemTime::UNIX_EPOCH,
+        };
+
+        self.jobs.push(Job {
+            name: name.into(),
+            schedule,
+            next: Some(next),
+            f: Box::new(f),
+        });
+
+        self
+    }
+
+    /// Restores the given history, rescheduling each job from its last execution.
+    ///
+    /// Jobs must be registered before restoring the history for them to be rescheduled.
+    pub fn with_history(mut self, history: impl IntoIterator<Item = Execution>) -> Self {
+        self.history.extend(history);
+        for job in &mut self.jobs {
+            let last_run = self
+                .history
+                .iter()
+                .filter(|execution| execution.job == job.name)
+                .map(|execution| execution.at)
+                .max();
+
+            if let Some(last_run) = last_run {
+                job.next = job.next_after(last_run);
+            }
+        }
+
+        self
+    }
+
+    /// Runs every job that is due at the current moment of the given clock, returning the record
+    /// of each execution.
+    pub fn tick(&mut self, schema: &Schema<T>, clock: &impl Clock) -> &[Execution] {
+        let now = clock.now();
+        let start = self.history.len();
+        for job in &mut self.jobs {
+            if job.next.is_none_or(|next| next > now) {
+                continue;
+            }
+
+            let error = (job.f)(schema).err().map(|err| err.to_string());
+            if let Some(error) = &error {
+                tracing::error!(job = job.name, error, "running scheduled job");
+            }
+
+            self.history.push(Execution {
+                job: job.name.clone(),
+                at: now,
+                error,
+            });
+
+            job.next = job.next_after(now);
+        }
+
+        &self.history[start..]
+    }
+
+    /// Returns the earliest moment any job is due at, if any.
+    pub fn next_run(&self) -> Option<SystemTime> {
+        self.jobs.iter().filter_map(|job| job.next).min()
+    }
+
+    /// Returns the record of all the executions so far, from the oldest to the newest.
+    pub fn history(&self) -> &[Execution] {
+        &self.history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use crate::{
+        clock::fixtures::MockClock,
+        graph::{fixtures::FakeNode, Graph},
+        schema::{Error, Schema},
+    };
+
+    use super::{Execution, Schedule, Scheduler};
+
+    type Node = FakeNode<'static, usize>;
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn scheduler_runs_due_jobs() {
+        struct Test<'a> {
+            name: &'a str,
+            scheduler: Scheduler<Node>,
+            ticks: Vec<u64>,
+            output: Vec<(&'a str, u64)>,
+        }
+
+        vec![
+            Test {
+                name: "periodic job runs on first tick and after each period",
+                scheduler: Scheduler::default().with_job(
+                    "reindex",
+                    Schedule::Every(Duration::from_secs(10)),
+                    |_| Ok(()),
+                ),
+                ticks: vec![5, 10, 15, 16],
+                output: vec![("reindex", 5), ("reindex", 15)],
+            },
+            Test {
+                name: "one-shot job runs once after its moment",
+                scheduler: Scheduler::default().with_job("gc", Schedule::At(at(10)), |_| Ok(())),
+                ticks: vec![5, 12, 20],
+                output: vec![("gc", 12)],
+            },
+            Test {
+                name: "restored history reschedules jobs",
+                scheduler: Scheduler::default()
+                    .with_job("reindex", Schedule::Every(Duration::from_secs(10)), |_| {
+                        Ok(())
+                    })
+                    .with_job("gc", Schedule::At(at(10)), |_| Ok(()))
+                    .with_history([
+                        Execution {
+                            job: "reindex".into(),
+                            at: at(8),
+                            error: None,
+                        },
+                        Execution {
+                            job: "gc".into(),
+                            at: at(11),
+                            error: None,
+                        },
+                    ]),
+                ticks: vec![12, 18],
+                output: vec![("reindex", 8), ("gc", 11), ("reindex", 18)],
+            },
+        ]
+        .into_iter()
+        .for_each(|mut test| {
+            let schema = Schema::from(Graph::<Node>::default());
+            let clock = MockClock::default();
+            for tick in test.ticks {
+                clock.set(at(tick));
+                test.scheduler.tick(&schema, &clock);
+            }
+
+            let output: Vec<_> = test
+                .scheduler
+                .history()
+                .iter()
+                .map(|execution| (execution.job.as_str(), execution.at))
+                .collect();
+
+            let want: Vec<_> = test
+                .output
+                .into_iter()
+                .map(|(job, secs)| (job, at(secs)))
+                .collect();
+
+            assert_eq!(output, want, "{}", test.name);
+        });
+    }
+
+    #[test]
+    fn scheduler_records_failures() {
+        let schema = Schema::from(Graph::<Node>::default());
+        let mut scheduler = Scheduler::default().with_job(
+            "snapshot",
+            Schedule::Every(Duration::from_secs(1)),
+            |_| Err(Error::custom("disk full")),
+        );
+
+        let executions = scheduler.tick(&schema, &MockClock::new(at(1)));
+        assert_eq!(executions.len(), 1);
+        assert_eq!(executions[0].error.as_deref(), Some("disk full"));
+        assert_eq!(scheduler.next_run(), Some(at(2)));
+    }
+}
@@ -8,7 +8,10 @@ use crate::{
     id::Identify,
 };
 
-use super::{guard::SchemaWriteGuard, resource::ResourceSet, trigger::TriggerSet, Result, Schema};
+use super::{
+    guard::SchemaWriteGuard, middleware::MiddlewareSet, resource::ResourceSet, trigger::TriggerSet,
+    Error, Result, Schema,
+};
 
 /// Represents a set of operations that must be perfomed as a whole.
 pub trait Transaction: Sized {
@@ -55,9 +58,25 @@ where
     where
         F: FnOnce(Context<'_, Self::Target>) -> Result<U>,
     {
-        f((&self).into()).inspect(|_| {
-            self.commit();
-        })
+        let mut f = Some(f);
+        let mut output = None;
+
+        {
+            let ctx: Context<'_, T> = (&self).into();
+            self.schema.middlewares().handle(&ctx, &mut || {
+                let f = f
+                    .take()
+                    .ok_or_else(|| Error::custom("transaction executed more than once"))?;
+
+                output = Some(f((&self).into())?);
+                Ok(())
+            })?;
+        }
+
+        // A middleware may have skipped the transaction without failing.
+        let output = output.ok_or(Error::Noop)?;
+        self.commit();
+        Ok(output)
     }
 }
 
@@ -461,6 +480,16 @@ where
         guard.push(Operation::Save(node));
     }
 
+    /// Discards all the operations registered so far in this context.
+    pub fn discard(&self) {
+        let mut guard = match self.operations.write() {
+            Ok(ops) => ops,
+            Err(err) => err.into_inner(),
+        };
+
+        guard.clear();
+    }
+
     /// Registers the delete operation as part of the transaction.
     pub fn delete(&self, node_id: T::Id) {
         let mut guard = match self.operations.write() {
@@ -481,6 +510,11 @@ where
         self.schema.triggers()
     }
 
+    /// Returns a reference to the underlying schema's [`MiddlewareSet`].
+    pub fn middlewares(&self) -> &MiddlewareSet<T> {
+        self.schema.middlewares()
+    }
+
     /// Returns a reference to the transaction's target.
     pub fn target(&self) -> &Target<T> {
         &self.target
@@ -512,6 +546,22 @@ where
     }
 }
 
+impl<T> Source for Ctx<'_, T>
+where
+    T: Identify + Clone,
+    T::Id: Ord + PartialEq,
+{
+    type Node = T;
+
+    fn get(&self, id: &<Self::Node as Identify>::Id) -> Option<Self::Node> {
+        self.context.get(id)
+    }
+
+    fn contains(&self, id: &<Self::Node as Identify>::Id) -> bool {
+        self.context.contains(id)
+    }
+}
+
 impl<'a, T> From<&'a Context<'a, T>> for Ctx<'a, T>
 where
     T: Identify,
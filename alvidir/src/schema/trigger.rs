@@ -16,6 +16,11 @@ where
 {
     /// Executes the trigger.
     fn execute(&self, ctx: &Context<'_, T>) -> Result<()>;
+
+    /// Returns the name of the trigger, being its type name by default.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 #[macro_export]
@@ -68,7 +73,11 @@ where
             return Ok(());
         };
 
-        triggers.iter().try_for_each(|trigger| trigger.execute(ctx))
+        triggers.iter().try_for_each(|trigger| {
+            let result = trigger.execute(ctx);
+            ctx.middlewares().observe(trigger.name(), &result);
+            result
+        })
     }
 }
 
@@ -149,6 +158,10 @@ where
     fn execute(&self, ctx: &Context<'_, T>) -> Result<()> {
         self.trigger.execute(ctx)
     }
+
+    fn name(&self) -> &'static str {
+        self.trigger.name()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,108 @@
+//! An [`Embedder`] implementation based on feature hashing.
+
+use crate::{Embedder, Embedding};
+
+/// The FNV-1a offset basis for 64 bits hashes.
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+/// The FNV-1a prime for 64 bits hashes.
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// The weight of a whole word in the embedding.
+const WORD_WEIGHT: f32 = 1.;
+/// The weight of a character trigram in the embedding.
+const TRIGRAM_WEIGHT: f32 = 0.5;
+
+/// An [`Embedder`] hashing the words of a text, and their character trigrams, into a vector of
+/// fixed dimensions.
+///
+/// Requires no model at all, at the cost of capturing lexical similarity only.
+#[derive(Debug, Clone, Copy)]
+pub struct HashingEmbedder {
+    dimensions: usize,
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self { dimensions: 256 }
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn embed(&self, text: &str) -> Embedding {
+        let mut embedding = vec![0.; self.dimensions];
+        if self.dimensions == 0 {
+            return embedding;
+        }
+
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .for_each(|word| {
+                embedding[self.bucket(word.as_bytes())] += WORD_WEIGHT;
+
+                let chars: Vec<char> = format!("#{word}#").chars().collect();
+                chars.windows(3).for_each(|trigram| {
+                    let trigram: String = trigram.iter().collect();
+                    embedding[self.bucket(trigram.as_bytes())] += TRIGRAM_WEIGHT;
+                });
+            });
+
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0. {
+            embedding.iter_mut().for_each(|x| *x /= norm);
+        }
+
+        embedding
+    }
+}
+
+impl HashingEmbedder {
+    /// Returns an embedder producing vectors of the given dimensions.
+    pub fn new(dimensions: usize) -> Self {
+        Self { dimensions }
+    }
+
+    /// Returns the position in the embedding of the given feature.
+    fn bucket(&self, feature: &[u8]) -> usize {
+        let hash = feature.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+        });
+
+        (hash % self.dimensions as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Embedder, EmbeddingIndex};
+
+    use super::HashingEmbedder;
+
+    #[test]
+    fn similar_texts_are_closer() {
+        let embedder = HashingEmbedder::default();
+
+        let mut index = EmbeddingIndex::default();
+        index.insert("cats", embedder.embed("Cats are small furry animals."));
+        index.insert(
+            "rust",
+            embedder.embed("Rust is a systems programming language."),
+        );
+
+        let nearest = index.nearest(&embedder.embed("a furry cat"), 1);
+        assert_eq!(nearest[0].0, &"cats");
+
+        let nearest = index.nearest(&embedder.embed("programming in rust"), 1);
+        assert_eq!(nearest[0].0, &"rust");
+    }
+
+    #[test]
+    fn embedding_has_unit_norm() {
+        let embedding = HashingEmbedder::new(16).embed("hello world");
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.).abs() < f32::EPSILON * 4.);
+
+        let embedding = HashingEmbedder::new(16).embed("");
+        assert!(embedding.iter().all(|x| *x == 0.));
+    }
+}
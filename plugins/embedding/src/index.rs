@@ -0,0 +1,130 @@
+//! The embedding index definition.
+
+use std::collections::BTreeMap;
+
+use crate::Embedding;
+
+/// An index of embeddings by node id.
+#[derive(Debug)]
+pub struct EmbeddingIndex<Id> {
+    embeddings: BTreeMap<Id, Embedding>,
+}
+
+impl<Id> Default for EmbeddingIndex<Id> {
+    fn default() -> Self {
+        Self {
+            embeddings: Default::default(),
+        }
+    }
+}
+
+impl<Id> EmbeddingIndex<Id>
+where
+    Id: Ord,
+{
+    /// Inserts the embedding of the given node, overwriting any previous one.
+    pub fn insert(&mut self, id: Id, embedding: Embedding) {
+        self.embeddings.insert(id, embedding);
+    }
+
+    /// Removes the embedding of the given node, if any.
+    pub fn remove(&mut self, id: &Id) {
+        self.embeddings.remove(id);
+    }
+
+    /// Returns the amount of embeddings in the index.
+    pub fn len(&self) -> usize {
+        self.embeddings.len()
+    }
+
+    /// Returns true if, and only if, the index has no embeddings.
+    pub fn is_empty(&self) -> bool {
+        self.embeddings.is_empty()
+    }
+
+    /// Returns, at most, the k nodes whose embeddings are the most similar to the given one,
+    /// from the most to the least similar, along with their cosine similarity.
+    pub fn nearest(&self, query: &[f32], k: usize) -> Vec<(&Id, f32)> {
+        let mut similarities: Vec<_> = self
+            .embeddings
+            .iter()
+            .map(|(id, embedding)| (id, cosine_similarity(query, embedding)))
+            .collect();
+
+        similarities.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        similarities.truncate(k);
+        similarities
+    }
+}
+
+/// Returns the cosine of the angle between the given vectors, or zero if any of them is null.
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(a, b)| a * b).sum();
+    let norm_a = a.iter().map(|a| a * a).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|b| b * b).sum::<f32>().sqrt();
+
+    if norm_a == 0. || norm_b == 0. {
+        return 0.;
+    }
+
+    dot / (norm_a * norm_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EmbeddingIndex;
+
+    #[test]
+    fn nearest_embeddings_in_index() {
+        struct Test<'a> {
+            name: &'a str,
+            index: EmbeddingIndex<usize>,
+            query: Vec<f32>,
+            k: usize,
+            output: Vec<usize>,
+        }
+
+        let index = || {
+            let mut index = EmbeddingIndex::default();
+            index.insert(1, vec![1., 0.]);
+            index.insert(2, vec![0., 1.]);
+            index.insert(3, vec![1., 1.]);
+            index
+        };
+
+        vec![
+            Test {
+                name: "empty index",
+                index: EmbeddingIndex::default(),
+                query: vec![1., 0.],
+                k: 3,
+                output: vec![],
+            },
+            Test {
+                name: "ordered by similarity",
+                index: index(),
+                query: vec![1., 0.1],
+                k: 3,
+                output: vec![1, 3, 2],
+            },
+            Test {
+                name: "limited to k",
+                index: index(),
+                query: vec![0.1, 1.],
+                k: 1,
+                output: vec![2],
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let output: Vec<_> = test
+                .index
+                .nearest(&test.query, test.k)
+                .into_iter()
+                .map(|(id, _)| *id)
+                .collect();
+
+            assert_eq!(output, test.output, "{}", test.name);
+        });
+    }
+}
@@ -0,0 +1,17 @@
+//! A vector index of embeddings for semantic search.
+
+mod hashing;
+mod index;
+mod plugin;
+pub use hashing::HashingEmbedder;
+pub use index::EmbeddingIndex;
+pub use plugin::EmbeddingPlugin;
+
+/// The vector representation of a text.
+pub type Embedding = Vec<f32>;
+
+/// A model computing the embedding of arbitrary texts.
+pub trait Embedder {
+    /// Returns the embedding of the given text.
+    fn embed(&self, text: &str) -> Embedding;
+}
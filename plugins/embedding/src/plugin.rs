@@ -0,0 +1,91 @@
+//! The plugin implementation for [`EmbeddingIndex`].
+
+use std::marker::PhantomData;
+
+use alvidir::{prelude::*, property::Extract};
+
+use crate::{Embedder, EmbeddingIndex};
+
+/// Implements the [`Plugin`] trait for an arbitrary extractor of texts from a source of type T,
+/// indexing the embedding of these texts as computed by the given embedder.
+pub struct EmbeddingPlugin<T, Extractor, E> {
+    extractor: Extractor,
+    embedder: E,
+    node: PhantomData<T>,
+}
+
+impl<T, Extractor, E> EmbeddingPlugin<T, Extractor, E> {
+    pub fn new(extractor: Extractor, embedder: E) -> Self {
+        Self {
+            extractor,
+            embedder,
+            node: PhantomData,
+        }
+    }
+}
+
+impl<T, Extractor, E> EmbeddingPlugin<T, Extractor, E>
+where
+    T: 'static + Identify,
+    T::Id: Ord + Clone,
+    Extractor: 'static + Extract<T, Target = String>,
+    E: 'static + Embedder,
+{
+    /// Returns the id of the given node along with the embedding of its texts.
+    fn embed(node: &T, extractor: &Extractor, embedder: &E) -> (T::Id, Vec<f32>) {
+        let text = extractor.all(node).join("\n");
+        (node.id().clone(), embedder.embed(&text))
+    }
+
+    fn on_save(
+        _: Ctx<T>,
+        target: Target<T>,
+        index: Res<EmbeddingIndex<T::Id>>,
+        extractor: Res<Extractor>,
+        embedder: Res<E>,
+    ) -> Result<()> {
+        let Some((node_id, embedding)) = (target, extractor, embedder)
+            .with(|(target, extractor, embedder)| Self::embed(target, extractor, embedder))
+        else {
+            return Ok(());
+        };
+
+        index.with_mut(|index| index.insert(node_id, embedding));
+        Ok(())
+    }
+
+    fn on_delete(_: Ctx<T>, target: Target<T>, index: Res<EmbeddingIndex<T::Id>>) -> Result<()> {
+        let Some(node_id) = target.with(|target| target.id().clone()) else {
+            return Ok(());
+        };
+
+        index.with_mut(|index| index.remove(&node_id));
+        Ok(())
+    }
+}
+
+impl<T, Extractor, E> Plugin<T> for EmbeddingPlugin<T, Extractor, E>
+where
+    T: 'static + Identify,
+    T::Id: Ord + Clone,
+    Extractor: 'static + Extract<T, Target = String>,
+    E: 'static + Embedder,
+{
+    fn install(self, schema: Schema<T>) -> Schema<T>
+    where
+        T: Identify,
+    {
+        let mut index = EmbeddingIndex::default();
+        for node in schema.read().into_iter() {
+            let (node_id, embedding) = Self::embed(node, &self.extractor, &self.embedder);
+            index.insert(node_id, embedding);
+        }
+
+        schema
+            .with_resource(self.extractor)
+            .with_resource(self.embedder)
+            .with_resource(index)
+            .with_trigger(AfterSave, Self::on_save)
+            .with_trigger(AfterDelete, Self::on_delete)
+    }
+}
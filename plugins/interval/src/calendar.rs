@@ -0,0 +1,755 @@
+//! Calendar bounds and the ranges in between them, parsed from their most common string formats.
+//!
+//! Years are numbered astronomically, so 1 BC is year 0 and 2 BC is year -1. Dates follow the
+//! proleptic Gregorian calendar, and date-times carry no time zone.
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    circa::{Circa, Uncertain},
+    Bound, Interval,
+};
+
+/// The separator in between the bounds of a [`Range`].
+const RANGE_SEPARATOR: &str = "..";
+
+/// The prefixes marking a [`Circa`] range.
+const CIRCA_PREFIXES: [&str; 2] = ["circa", "c."];
+
+/// The separators in between a [`Circa`] range and its radius.
+const RADIUS_SEPARATORS: [&str; 2] = ["±", "+-"];
+
+/// The reason why a calendar value cannot be built.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// The string does not follow any of the supported formats.
+    #[error("{0:?} is not a valid {1}")]
+    Format(String, &'static str),
+    /// A component of the value is not in the range it must be.
+    #[error("{0} {1} is out of range")]
+    OutOfRange(&'static str, i64),
+    /// The lowest bound of a range is greater than the highest one.
+    #[error("range must not end before it starts")]
+    Reversed,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Returns the given component as a number in the given range, if any.
+fn component<T>(s: &str, name: &'static str, min: i64, max: i64) -> Result<T>
+where
+    T: TryFrom<i64>,
+{
+    let value: i64 = s.parse().map_err(|_| Error::Format(s.to_string(), name))?;
+
+    if !(min..=max).contains(&value) {
+        return Err(Error::OutOfRange(name, value));
+    }
+
+    T::try_from(value).map_err(|_| Error::OutOfRange(name, value))
+}
+
+/// A year of the calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Year(pub i32);
+
+impl Year {
+    /// Returns true if, and only if, self has 366 days.
+    pub fn is_leap(self) -> bool {
+        self.0 % 4 == 0 && (self.0 % 100 != 0 || self.0 % 400 == 0)
+    }
+}
+
+impl Uncertain for Year {
+    /// An amount of years.
+    type Radius = u32;
+
+    fn earliest(self, radius: Self::Radius) -> Self {
+        Self(self.0.saturating_sub_unsigned(radius))
+    }
+
+    fn latest(self, radius: Self::Radius) -> Self {
+        Self(self.0.saturating_add_unsigned(radius))
+    }
+}
+
+impl FromStr for Year {
+    type Err = Error;
+
+    /// Parses a year in the format `YYYY`, or `-YYYY` for years before year 0.
+    fn from_str(s: &str) -> Result<Self> {
+        s.trim()
+            .parse()
+            .map(Self)
+            .map_err(|_| Error::Format(s.to_string(), "year"))
+    }
+}
+
+impl fmt::Display for Year {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Interval for Year {
+    type Bound = Self;
+
+    fn lo(&self) -> Self::Bound {
+        *self
+    }
+
+    fn hi(&self) -> Self::Bound {
+        *self
+    }
+}
+
+/// A day of the calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: Year,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// Returns the date of the given day, failing if such day does not exist.
+    pub fn new(year: i32, month: u8, day: u8) -> Result<Self> {
+        let year = Year(year);
+        if !(1..=12).contains(&month) {
+            return Err(Error::OutOfRange("month", month.into()));
+        }
+
+        let days = match month {
+            2 if year.is_leap() => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+
+        if !(1..=days).contains(&day) {
+            return Err(Error::OutOfRange("day", day.into()));
+        }
+
+        Ok(Self { year, month, day })
+    }
+
+    pub fn year(&self) -> Year {
+        self.year
+    }
+
+    /// Returns the same day of the given year, or the 28th if self is the 29th of February and the
+    /// given year is not a leap one.
+    fn with_year(self, year: Year) -> Self {
+        let day = match (self.month, self.day) {
+            (2, 29) if !year.is_leap() => 28,
+            _ => self.day,
+        };
+
+        Self { year, day, ..self }
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+}
+
+impl Uncertain for Date {
+    /// An amount of years.
+    type Radius = u32;
+
+    fn earliest(self, radius: Self::Radius) -> Self {
+        self.with_year(self.year.earliest(radius))
+    }
+
+    fn latest(self, radius: Self::Radius) -> Self {
+        self.with_year(self.year.latest(radius))
+    }
+}
+
+impl FromStr for Date {
+    type Err = Error;
+
+    /// Parses a date in either the `YYYY-MM-DD` or the `DD/MM/YYYY` format.
+    fn from_str(s: &str) -> Result<Self> {
+        let trimmed = s.trim();
+        let (sign, unsigned) = match trimmed.strip_prefix('-') {
+            Some(unsigned) => (-1, unsigned),
+            None => (1, trimmed),
+        };
+
+        let dashed: Vec<_> = unsigned.split('-').collect();
+        let slashed: Vec<_> = trimmed.split('/').collect();
+        let (year, month, day) = match (&dashed[..], &slashed[..]) {
+            (&[year, month, day], _) => (year, month, day),
+            (_, &[day, month, year]) if sign > 0 => (year, month, day),
+            _ => return Err(Error::Format(s.to_string(), "date")),
+        };
+
+        let year: i32 = component(year, "year", 0, i32::MAX.into())?;
+        Self::new(
+            sign * year,
+            component(month, "month", 1, 12)?,
+            component(day, "day", 1, 31)?,
+        )
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.year.0 < 0 {
+            write!(f, "-")?;
+        }
+
+        write!(
+            f,
+            "{:04}-{:02}-{:02}",
+            self.year.0.unsigned_abs(),
+            self.month,
+            self.day
+        )
+    }
+}
+
+impl Interval for Date {
+    type Bound = Self;
+
+    fn lo(&self) -> Self::Bound {
+        *self
+    }
+
+    fn hi(&self) -> Self::Bound {
+        *self
+    }
+}
+
+/// A moment of a day of the calendar, up to seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    date: Date,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl DateTime {
+    /// Returns the given moment of the given date, failing if such moment does not exist.
+    pub fn new(date: Date, hour: u8, minute: u8, second: u8) -> Result<Self> {
+        if hour > 23 {
+            return Err(Error::OutOfRange("hour", hour.into()));
+        }
+
+        if minute > 59 {
+            return Err(Error::OutOfRange("minute", minute.into()));
+        }
+
+        if second > 59 {
+            return Err(Error::OutOfRange("second", second.into()));
+        }
+
+        Ok(Self {
+            date,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+}
+
+impl Uncertain for DateTime {
+    /// An amount of years.
+    type Radius = u32;
+
+    fn earliest(self, radius: Self::Radius) -> Self {
+        Self {
+            date: self.date.earliest(radius),
+            ..self
+        }
+    }
+
+    fn latest(self, radius: Self::Radius) -> Self {
+        Self {
+            date: self.date.latest(radius),
+            ..self
+        }
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = Error;
+
+    /// Parses a date-time as a [`Date`] followed by a `T` or a space and the time in the `HH:MM`
+    /// or the `HH:MM:SS` format.
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((date, time)) = s.trim().split_once(['T', ' ']) else {
+            return Err(Error::Format(s.to_string(), "date-time"));
+        };
+
+        let (hour, minute, second) = match time.split(':').collect::<Vec<_>>()[..] {
+            [hour, minute] => (hour, minute, "0"),
+            [hour, minute, second] => (hour, minute, second),
+            _ => return Err(Error::Format(s.to_string(), "date-time")),
+        };
+
+        Self::new(
+            date.parse()?,
+            component(hour, "hour", 0, 23)?,
+            component(minute, "minute", 0, 59)?,
+            component(second, "second", 0, 59)?,
+        )
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}T{:02}:{:02}:{:02}",
+            self.date, self.hour, self.minute, self.second
+        )
+    }
+}
+
+impl Interval for DateTime {
+    type Bound = Self;
+
+    fn lo(&self) -> Self::Bound {
+        *self
+    }
+
+    fn hi(&self) -> Self::Bound {
+        *self
+    }
+}
+
+/// The interval in between two calendar bounds, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Range<B> {
+    lo: B,
+    hi: B,
+}
+
+/// A range of years.
+pub type YearRange = Range<Year>;
+
+/// A range of dates.
+pub type DateRange = Range<Date>;
+
+/// A range of date-times.
+pub type DateTimeRange = Range<DateTime>;
+
+impl<B> Range<B>
+where
+    B: Bound,
+{
+    /// Returns the range in between the given bounds, failing if lo is greater than hi.
+    pub fn new(lo: B, hi: B) -> Result<Self> {
+        if lo > hi {
+            return Err(Error::Reversed);
+        }
+
+        Ok(Self { lo, hi })
+    }
+}
+
+impl<B> From<B> for Range<B>
+where
+    B: Copy,
+{
+    fn from(bound: B) -> Self {
+        Self {
+            lo: bound,
+            hi: bound,
+        }
+    }
+}
+
+impl<B> FromStr for Range<B>
+where
+    B: Bound + FromStr<Err = Error>,
+{
+    type Err = Error;
+
+    /// Parses a range as two bounds separated by `..`, or a single bound for a range containing
+    /// it only.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once(RANGE_SEPARATOR) {
+            Some((lo, hi)) => Self::new(lo.parse()?, hi.parse()?),
+            None => s.parse::<B>().map(Self::from),
+        }
+    }
+}
+
+impl<B> fmt::Display for Range<B>
+where
+    B: PartialEq + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.lo == self.hi {
+            return write!(f, "{}", self.lo);
+        }
+
+        write!(f, "{}{RANGE_SEPARATOR}{}", self.lo, self.hi)
+    }
+}
+
+impl<B> Interval for Range<B>
+where
+    B: Bound,
+{
+    type Bound = B;
+
+    fn lo(&self) -> Self::Bound {
+        self.lo
+    }
+
+    fn hi(&self) -> Self::Bound {
+        self.hi
+    }
+}
+
+impl<B> FromStr for Circa<Range<B>>
+where
+    B: Uncertain<Radius = u32> + FromStr<Err = Error>,
+{
+    type Err = Error;
+
+    /// Parses a range as in [`Range`], optionally preceded by `circa` or `c.` and followed by `±`
+    /// (or `+-`) and the radius in years, e.g. `circa 1800 ± 5y`.
+    fn from_str(s: &str) -> Result<Self> {
+        let trimmed = s.trim();
+        let range = CIRCA_PREFIXES
+            .into_iter()
+            .find_map(|prefix| trimmed.strip_prefix(prefix))
+            .unwrap_or(trimmed);
+
+        let (range, radius) = RADIUS_SEPARATORS
+            .into_iter()
+            .find_map(|separator| range.split_once(separator))
+            .map(|(range, radius)| {
+                let radius = radius.trim();
+                (range, radius.strip_suffix('y').unwrap_or(radius))
+            })
+            .unwrap_or((range, "0"));
+
+        Ok(Circa::new(
+            range.parse()?,
+            component(radius, "radius", 0, u32::MAX.into())?,
+        ))
+    }
+}
+
+impl<B> fmt::Display for Circa<Range<B>>
+where
+    B: Uncertain<Radius = u32> + fmt::Display,
+{
+    /// Writes the range as in [`Range`], preceded by `circa` and followed by the radius unless it
+    /// is zero.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.radius() == 0 {
+            return write!(f, "{}", self.interval());
+        }
+
+        write!(
+            f,
+            "{} {} {} {}y",
+            CIRCA_PREFIXES[0],
+            self.interval(),
+            RADIUS_SEPARATORS[0],
+            self.radius()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        circa::{Circa, Overlap},
+        Interval, IntervalExt,
+    };
+
+    use super::{Date, DateRange, DateTime, DateTimeRange, Error, Result, Year, YearRange};
+
+    #[test]
+    fn parse_year_range() {
+        struct Test {
+            name: &'static str,
+            input: &'static str,
+            output: Result<YearRange>,
+        }
+
+        vec![
+            Test {
+                name: "single year",
+                input: "1800",
+                output: Ok(Year(1800).into()),
+            },
+            Test {
+                name: "years before year 0",
+                input: "-44..-40",
+                output: YearRange::new(Year(-44), Year(-40)),
+            },
+            Test {
+                name: "surrounding whitespaces",
+                input: " 1800 .. 1805 ",
+                output: YearRange::new(Year(1800), Year(1805)),
+            },
+            Test {
+                name: "reversed range",
+                input: "1805..1800",
+                output: Err(Error::Reversed),
+            },
+            Test {
+                name: "not a year",
+                input: "18th",
+                output: Err(Error::Format("18th".to_string(), "year")),
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            assert_eq!(
+                test.input.parse::<YearRange>(),
+                test.output,
+                "{}",
+                test.name
+            );
+        });
+    }
+
+    #[test]
+    fn parse_date_range() {
+        struct Test {
+            name: &'static str,
+            input: &'static str,
+            output: Result<DateRange>,
+        }
+
+        vec![
+            Test {
+                name: "iso format",
+                input: "2024-02-29",
+                output: Date::new(2024, 2, 29).map(Into::into),
+            },
+            Test {
+                name: "day first format",
+                input: "29/02/2024..01/03/2024",
+                output: DateRange::new(
+                    Date::new(2024, 2, 29).unwrap(),
+                    Date::new(2024, 3, 1).unwrap(),
+                ),
+            },
+            Test {
+                name: "date before year 0",
+                input: "-0044-03-15",
+                output: Date::new(-44, 3, 15).map(Into::into),
+            },
+            Test {
+                name: "day not in a common year",
+                input: "2023-02-29",
+                output: Err(Error::OutOfRange("day", 29)),
+            },
+            Test {
+                name: "month out of range",
+                input: "2023-13-01",
+                output: Err(Error::OutOfRange("month", 13)),
+            },
+            Test {
+                name: "unknown format",
+                input: "2023.01.01",
+                output: Err(Error::Format("2023.01.01".to_string(), "date")),
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            assert_eq!(
+                test.input.parse::<DateRange>(),
+                test.output,
+                "{}",
+                test.name
+            );
+        });
+    }
+
+    #[test]
+    fn parse_date_time_range() {
+        struct Test {
+            name: &'static str,
+            input: &'static str,
+            output: Result<DateTimeRange>,
+        }
+
+        let date = Date::new(2024, 1, 1).unwrap();
+        vec![
+            Test {
+                name: "without seconds",
+                input: "2024-01-01T10:30",
+                output: DateTime::new(date, 10, 30, 0).map(Into::into),
+            },
+            Test {
+                name: "space separated",
+                input: "2024-01-01 10:30:15..2024-01-01 11:00",
+                output: DateTimeRange::new(
+                    DateTime::new(date, 10, 30, 15).unwrap(),
+                    DateTime::new(date, 11, 0, 0).unwrap(),
+                ),
+            },
+            Test {
+                name: "hour out of range",
+                input: "2024-01-01T24:00",
+                output: Err(Error::OutOfRange("hour", 24)),
+            },
+            Test {
+                name: "missing time",
+                input: "2024-01-01",
+                output: Err(Error::Format("2024-01-01".to_string(), "date-time")),
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            assert_eq!(
+                test.input.parse::<DateTimeRange>(),
+                test.output,
+                "{}",
+                test.name
+            );
+        });
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let input = "1800..1805";
+        assert_eq!(input.parse::<YearRange>().unwrap().to_string(), input);
+
+        let input = "-0044-03-15";
+        assert_eq!(input.parse::<DateRange>().unwrap().to_string(), input);
+
+        let input = "2024-01-01T10:30:15..2024-01-02T00:00:00";
+        assert_eq!(input.parse::<DateTimeRange>().unwrap().to_string(), input);
+
+        let input = "circa 2024-02-29 ± 5y";
+        assert_eq!(
+            input.parse::<Circa<DateRange>>().unwrap().to_string(),
+            input
+        );
+
+        let input = "1800..1805";
+        assert_eq!(
+            input.parse::<Circa<YearRange>>().unwrap().to_string(),
+            input
+        );
+    }
+
+    #[test]
+    fn parse_circa_range() {
+        struct Test {
+            name: &'static str,
+            input: &'static str,
+            output: Result<Circa<YearRange>>,
+        }
+
+        vec![
+            Test {
+                name: "circa with radius",
+                input: "circa 1800 ± 5y",
+                output: Ok(Circa::new(Year(1800).into(), 5)),
+            },
+            Test {
+                name: "abbreviated circa with ascii separator",
+                input: "c. 1790..1810 +- 2",
+                output: YearRange::new(Year(1790), Year(1810)).map(|range| Circa::new(range, 2)),
+            },
+            Test {
+                name: "without radius",
+                input: "circa -44",
+                output: Ok(Circa::new(Year(-44).into(), 0)),
+            },
+            Test {
+                name: "negative radius",
+                input: "circa 1800 ± -5y",
+                output: Err(Error::OutOfRange("radius", -5)),
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            assert_eq!(
+                test.input.parse::<Circa<YearRange>>(),
+                test.output,
+                "{}",
+                test.name
+            );
+        });
+    }
+
+    #[test]
+    fn circa_overlap() {
+        struct Test {
+            name: &'static str,
+            overlap: Overlap,
+            other: &'static str,
+            intersects: bool,
+        }
+
+        vec![
+            Test {
+                name: "possible overlap within the radius",
+                overlap: Overlap::Possible,
+                other: "1806",
+                intersects: true,
+            },
+            Test {
+                name: "possible overlap beyond the radius",
+                overlap: Overlap::Possible,
+                other: "1812",
+                intersects: false,
+            },
+            Test {
+                name: "exact overlap within the radius",
+                overlap: Overlap::Exact,
+                other: "1806",
+                intersects: false,
+            },
+            Test {
+                name: "exact overlap within the range",
+                overlap: Overlap::Exact,
+                other: "1802..1803",
+                intersects: true,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let circa = "circa 1795..1802 ± 5y"
+                .parse::<Circa<YearRange>>()
+                .unwrap()
+                .with_overlap(test.overlap);
+
+            let other = Circa::new(test.other.parse().unwrap(), 0).with_overlap(test.overlap);
+            assert_eq!(circa.intersects(&other), test.intersects, "{}", test.name);
+        });
+    }
+
+    #[test]
+    fn circa_date_on_leap_day() {
+        let date: Circa<DateRange> = "circa 2024-02-29 ± 1y".parse().unwrap();
+        assert_eq!(date.lo(), Date::new(2023, 2, 28).unwrap());
+        assert_eq!(date.hi(), Date::new(2025, 2, 28).unwrap());
+    }
+}
@@ -0,0 +1,125 @@
+//! Intervals known only approximately, like "circa 1800 ± 5 years".
+
+use std::{fmt::Debug, hash::Hash};
+
+use alvidir::property::Extract;
+
+use crate::{Bound, Interval};
+
+/// A bound that can be moved back and forth by some amount of uncertainty.
+pub trait Uncertain: Bound {
+    /// The amount a bound can be moved by.
+    type Radius: Copy + Debug + Ord + Hash;
+
+    /// Returns the earliest bound self may stand for, given the radius.
+    fn earliest(self, radius: Self::Radius) -> Self;
+
+    /// Returns the latest bound self may stand for, given the radius.
+    fn latest(self, radius: Self::Radius) -> Self;
+}
+
+/// How the uncertainty of a [`Circa`] is taken into account when intersecting intervals.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overlap {
+    /// Intervals intersect if they may overlap, widening their bounds by the radius.
+    #[default]
+    Possible,
+    /// Intervals intersect only if they overlap regardless of the radius, ignoring it.
+    Exact,
+}
+
+/// An interval whose bounds are uncertain up to a radius.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Circa<Intv>
+where
+    Intv: Interval,
+    Intv::Bound: Uncertain,
+{
+    interval: Intv,
+    radius: <Intv::Bound as Uncertain>::Radius,
+    overlap: Overlap,
+}
+
+impl<Intv> Circa<Intv>
+where
+    Intv: Interval,
+    Intv::Bound: Uncertain,
+{
+    pub fn new(interval: Intv, radius: <Intv::Bound as Uncertain>::Radius) -> Self {
+        Self {
+            interval,
+            radius,
+            overlap: Overlap::default(),
+        }
+    }
+
+    /// Sets how the radius is taken into account when intersecting self.
+    pub fn with_overlap(mut self, overlap: Overlap) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Returns the interval without uncertainty.
+    pub fn interval(&self) -> &Intv {
+        &self.interval
+    }
+
+    pub fn radius(&self) -> <Intv::Bound as Uncertain>::Radius {
+        self.radius
+    }
+
+    pub fn overlap(&self) -> Overlap {
+        self.overlap
+    }
+}
+
+impl<Intv> Interval for Circa<Intv>
+where
+    Intv: Interval,
+    Intv::Bound: Uncertain,
+{
+    type Bound = Intv::Bound;
+
+    fn lo(&self) -> Self::Bound {
+        match self.overlap {
+            Overlap::Possible => self.interval.lo().earliest(self.radius),
+            Overlap::Exact => self.interval.lo(),
+        }
+    }
+
+    fn hi(&self) -> Self::Bound {
+        match self.overlap {
+            Overlap::Possible => self.interval.hi().latest(self.radius),
+            Overlap::Exact => self.interval.hi(),
+        }
+    }
+}
+
+/// Extracts the [`Circa`] intervals of another extractor, all of them with the same [`Overlap`].
+pub struct WithOverlap<Extractor> {
+    extractor: Extractor,
+    overlap: Overlap,
+}
+
+impl<Extractor> WithOverlap<Extractor> {
+    pub fn new(extractor: Extractor, overlap: Overlap) -> Self {
+        Self { extractor, overlap }
+    }
+}
+
+impl<T, Extractor, Intv> Extract<T> for WithOverlap<Extractor>
+where
+    Extractor: Extract<T, Target = Circa<Intv>>,
+    Intv: Interval,
+    Intv::Bound: Uncertain,
+{
+    type Target = Circa<Intv>;
+
+    fn all(&self, source: &T) -> Vec<Self::Target> {
+        self.extractor
+            .all(source)
+            .into_iter()
+            .map(|interval| interval.with_overlap(self.overlap))
+            .collect()
+    }
+}
@@ -25,6 +25,15 @@ pub struct Date<T, const N: usize, Endian> {
     endian: PhantomData<Endian>,
 }
 
+impl<T, const N: usize, Endian> From<[T; N]> for Date<T, N, Endian> {
+    fn from(components: [T; N]) -> Self {
+        Self {
+            components,
+            endian: PhantomData,
+        }
+    }
+}
+
 impl<T, const N: usize, Endian> Eq for Date<T, N, Endian> where T: Eq {}
 
 impl<T, const N: usize, Endian> PartialEq for Date<T, N, Endian>
@@ -0,0 +1,195 @@
+//! Laws every [`Interval`] implementation must hold.
+//!
+//! Each law panics, naming the offending samples, if any of the given samples violates it. This
+//! allows implementors to check their types from their own tests, e.g. by calling [`check`] with
+//! a representative set of intervals.
+
+use std::fmt::Debug;
+
+use crate::{Bound, Interval, IntervalExt};
+
+/// Asserts that, for any a and b, a <= b and b <= a implies a == b.
+pub fn ordering_is_antisymmetric<B>(bounds: &[B])
+where
+    B: Bound + Debug,
+{
+    for a in bounds {
+        for b in bounds {
+            assert!(
+                !(a <= b && b <= a) || a == b,
+                "ordering must be antisymmetric: {a:?} <= {b:?} and {b:?} <= {a:?} but they are not equal"
+            );
+        }
+    }
+}
+
+/// Asserts that, for any a, b and c, a <= b and b <= c implies a <= c.
+pub fn ordering_is_transitive<B>(bounds: &[B])
+where
+    B: Bound + Debug,
+{
+    for a in bounds {
+        for b in bounds.iter().filter(|b| a <= *b) {
+            for c in bounds.iter().filter(|c| b <= *c) {
+                assert!(
+                    a <= c,
+                    "ordering must be transitive: {a:?} <= {b:?} <= {c:?} but not {a:?} <= {c:?}"
+                );
+            }
+        }
+    }
+}
+
+/// Asserts that the lowest bound of every interval is not greater than the highest one.
+pub fn bounds_are_sorted<Intv>(intervals: &[Intv])
+where
+    Intv: Interval + Debug,
+{
+    for interval in intervals {
+        assert!(
+            interval.lo() <= interval.hi(),
+            "lowest bound must not be greater than the highest one in {interval:?}"
+        );
+    }
+}
+
+/// Asserts that every interval intersects itself.
+pub fn intersection_is_reflexive<Intv>(intervals: &[Intv])
+where
+    Intv: Interval + Debug,
+{
+    for interval in intervals {
+        assert!(
+            interval.intersects(interval),
+            "{interval:?} must intersect itself"
+        );
+    }
+}
+
+/// Asserts that, for any a and b, a intersects b if, and only if, b intersects a.
+pub fn intersection_is_symmetric<Intv>(intervals: &[Intv])
+where
+    Intv: Interval + Debug,
+{
+    for a in intervals {
+        for b in intervals {
+            assert_eq!(
+                a.intersects(b),
+                b.intersects(a),
+                "intersection must be symmetric between {a:?} and {b:?}"
+            );
+        }
+    }
+}
+
+/// Asserts all the laws for the given intervals and their bounds.
+pub fn check<Intv>(intervals: &[Intv])
+where
+    Intv: Interval + Debug,
+    Intv::Bound: Debug,
+{
+    let bounds: Vec<_> = intervals
+        .iter()
+        .flat_map(|interval| [interval.lo(), interval.hi()])
+        .collect();
+
+    ordering_is_antisymmetric(&bounds);
+    ordering_is_transitive(&bounds);
+    bounds_are_sorted(intervals);
+    intersection_is_reflexive(intervals);
+    intersection_is_symmetric(intervals);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{interval_mock, IntervalMock};
+
+    #[test]
+    fn mock_intervals_hold_all_laws() {
+        let intervals: Vec<IntervalMock<usize>> = vec![
+            interval_mock!(0, 0),
+            interval_mock!(0, 2),
+            interval_mock!(1, 3),
+            interval_mock!(4, 9),
+        ];
+
+        super::check(&intervals);
+    }
+
+    #[test]
+    #[should_panic(expected = "lowest bound must not be greater than the highest one")]
+    fn reversed_interval_breaks_laws() {
+        let intervals: Vec<IntervalMock<usize>> = vec![interval_mock!(3, 1)];
+        super::check(&intervals);
+    }
+
+    #[cfg(feature = "date")]
+    #[test]
+    fn dates_hold_all_laws() {
+        use crate::date::{BigEndianDate, LittleEndianDate};
+
+        let dates: Vec<BigEndianDate<u16, 3>> = vec![
+            [2024, 1, 1].into(),
+            [2024, 12, 31].into(),
+            [2023, 6, 15].into(),
+        ];
+
+        super::check(&dates);
+
+        let dates: Vec<LittleEndianDate<u16, 3>> = vec![
+            [1, 1, 2024].into(),
+            [31, 12, 2024].into(),
+            [15, 6, 2023].into(),
+        ];
+
+        super::check(&dates);
+    }
+
+    #[cfg(feature = "calendar")]
+    #[test]
+    fn calendar_ranges_hold_all_laws() {
+        use crate::{
+            calendar::{DateRange, DateTimeRange, YearRange},
+            circa::{Circa, Overlap},
+        };
+
+        let years: Vec<YearRange> = ["-44..-40", "1800", "1790..1810", "2000..2024"]
+            .into_iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+
+        super::check(&years);
+
+        let dates: Vec<DateRange> = ["2024-01-01..2024-12-31", "29/02/2024", "2023-06-15"]
+            .into_iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+
+        super::check(&dates);
+
+        let date_times: Vec<DateTimeRange> = [
+            "2024-01-01T00:00..2024-01-01T23:59:59",
+            "2024-01-01 12:00",
+            "2023-12-31T23:59:59..2024-01-01T00:00",
+        ]
+        .into_iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+
+        super::check(&date_times);
+
+        let circas: Vec<Circa<YearRange>> = ["circa 1800 ± 5y", "c. 1790..1810 +- 2", "1812"]
+            .into_iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+
+        super::check(&circas);
+
+        let circas: Vec<_> = circas
+            .into_iter()
+            .map(|circa| circa.with_overlap(Overlap::Exact))
+            .collect();
+
+        super::check(&circas);
+    }
+}
@@ -1,10 +1,15 @@
 //! An interval search tree.
 
+#[cfg(feature = "calendar")]
+pub mod calendar;
+pub mod circa;
 #[cfg(feature = "date")]
 pub mod date;
+pub mod laws;
 mod node;
 mod plugin;
 mod tree;
+pub use plugin::IntervalPlugin;
 pub use tree::IntervalSearchTree;
 
 /// One of the limits in an [`Interval`].
@@ -49,7 +54,7 @@ pub mod fixtures {
     use super::{Bound, Interval};
 
     /// A mock implementation for the [`Interval`] trait.
-    #[derive(Default, Clone, PartialOrd, Ord)]
+    #[derive(Default, Clone)]
     pub struct IntervalMock<Bound> {
         lo_fn: Option<fn() -> Bound>,
         hi_fn: Option<fn() -> Bound>,
@@ -80,7 +80,7 @@ where
     pub fn delete(mut self: Box<Self>, interval: &Intv) -> Option<Box<Self>> {
         if &self.value == interval {
             return match (self.left, self.right) {
-                (Some(left), Some(right)) => Some(left.join(right)),
+                (Some(left), Some(right)) => Some(left.join(*right)),
                 (left, _) if left.is_some() => left,
                 (_, right) if right.is_some() => right,
                 _ => None,
@@ -88,9 +88,9 @@ where
         }
 
         if interval.lo() < self.value.lo() {
-            self.left = self.left.map(|left| left.delete(interval)).flatten();
+            self.left = self.left.and_then(|left| left.delete(interval));
         } else if interval.lo() > self.value.lo() {
-            self.right = self.right.map(|right| right.delete(interval)).flatten();
+            self.right = self.right.and_then(|right| right.delete(interval));
         }
 
         Some(self)
@@ -167,6 +167,16 @@ where
         immersion(self, interval, &mut f);
     }
 
+    /// Returns the lowest and highest bounds of all the intervals in the tree rooted by self.
+    pub fn span(&self) -> (Intv::Bound, Intv::Bound) {
+        let mut lowest = self;
+        while let Some(left) = &lowest.left {
+            lowest = left;
+        }
+
+        (lowest.value.lo(), self.max)
+    }
+
     /// Returns the total amount of intervals in the tree.
     pub fn count(&self) -> usize {
         let mut count = 1;
@@ -208,7 +218,7 @@ where
     }
 
     /// Given the root of a left (self) and right trees, joins them into a single one.
-    fn join(self: Box<Self>, right: Box<Self>) -> Box<Self> {
+    fn join(self, right: Self) -> Box<Self> {
         fn immersion<Intv>(
             root: Box<IntervalSearchTreeNode<Intv>>,
             mut intervals: Vec<Intv>,
@@ -235,14 +245,20 @@ where
     }
 
     /// Returns a vector with all the intervals in order.
-    fn into_inorder(self: Box<Self>) -> Vec<Intv> {
-        fn immersion<Intv>(node: Box<IntervalSearchTreeNode<Intv>>, v: &mut Vec<Intv>)
+    fn into_inorder(self) -> Vec<Intv> {
+        fn immersion<Intv>(node: IntervalSearchTreeNode<Intv>, v: &mut Vec<Intv>)
         where
             Intv: Interval,
         {
-            node.left.map(|left| immersion(left, v));
+            if let Some(left) = node.left {
+                immersion(*left, v);
+            }
+
             v.push(node.value);
-            node.right.map(|right| immersion(right, v));
+
+            if let Some(right) = node.right {
+                immersion(*right, v);
+            }
         }
 
         let mut v = Vec::with_capacity(self.count());
@@ -4,7 +4,10 @@ use std::marker::PhantomData;
 
 use alvidir::{prelude::*, property::Extract};
 
-use crate::{Interval, IntervalSearchTree};
+use crate::{
+    circa::{Circa, Overlap, Uncertain, WithOverlap},
+    Interval, IntervalSearchTree,
+};
 
 /// Stores the relation between a node from the graph and its interval.
 #[derive(Debug)]
@@ -51,9 +54,73 @@ type SearchTree<Id, Intv> = IntervalSearchTree<NodeInterval<Id, Intv>>;
 /// Implements the [`Plugin`] trait for an arbitrary extractor of intervals from a source of type T.
 pub struct IntervalPlugin<T, Extractor> {
     extractor: Extractor,
+    nodes: Vec<T>,
+    simultaneous: bool,
     node: PhantomData<T>,
 }
 
+impl<T, Extractor> IntervalPlugin<T, Extractor> {
+    pub fn new(extractor: Extractor) -> Self {
+        Self {
+            extractor,
+            nodes: Vec::default(),
+            simultaneous: true,
+            node: PhantomData,
+        }
+    }
+
+    /// Indexes the intervals of the given nodes, already in the graph, once installed.
+    pub fn with_nodes(mut self, nodes: impl IntoIterator<Item = T>) -> Self {
+        self.nodes.extend(nodes);
+        self
+    }
+
+    /// Sets whether the intervals of different nodes may intersect, rejecting the save of any
+    /// node with an interval intersecting one from another node otherwise.
+    pub fn with_simultaneity(mut self, simultaneous: bool) -> Self {
+        self.simultaneous = simultaneous;
+        self
+    }
+}
+
+impl<T, Extractor, Intv> IntervalPlugin<T, Extractor>
+where
+    Extractor: Extract<T, Target = Circa<Intv>>,
+    Intv: Interval,
+    Intv::Bound: Uncertain,
+{
+    /// Sets how the radius of the extracted intervals is taken into account when intersecting
+    /// them, including when checking their simultaneity.
+    pub fn with_overlap(self, overlap: Overlap) -> IntervalPlugin<T, WithOverlap<Extractor>> {
+        IntervalPlugin {
+            extractor: WithOverlap::new(self.extractor, overlap),
+            nodes: self.nodes,
+            simultaneous: self.simultaneous,
+            node: PhantomData,
+        }
+    }
+}
+
+/// Returns the intervals the extractor finds in the given node.
+fn node_intervals<T, Extractor>(
+    node: &T,
+    extractor: &Extractor,
+) -> Vec<NodeInterval<T::Id, Extractor::Target>>
+where
+    T: Identify,
+    T::Id: Clone,
+    Extractor: Extract<T>,
+{
+    extractor
+        .all(node)
+        .into_iter()
+        .map(|interval| NodeInterval {
+            node_id: node.id().clone(),
+            interval,
+        })
+        .collect()
+}
+
 impl<T, Extractor> IntervalPlugin<T, Extractor>
 where
     T: 'static + Identify,
@@ -61,21 +128,53 @@ where
     Extractor: 'static + Extract<T>,
     Extractor::Target: Interval + PartialEq,
 {
-    fn on_delete(
+    fn before_save(
         _: Ctx<T>,
         target: Target<T>,
         search_tree: Res<SearchTree<T::Id, Extractor::Target>>,
         extractor: Res<Extractor>,
     ) -> Result<()> {
-        let Some(intervals) = (target, extractor).with(|(target, factory)| {
-            factory.all(target).into_iter().map({
-                let node_id = target.id().clone();
-                move |interval| NodeInterval {
-                    node_id: node_id.clone(),
-                    interval,
-                }
+        let Some(intervals) =
+            (target, extractor).with(|(target, extractor)| node_intervals(target, extractor))
+        else {
+            return Ok(());
+        };
+
+        let simultaneous = search_tree.with(|search_tree| {
+            intervals.iter().any(|interval| {
+                let mut simultaneous = false;
+                search_tree.for_each_intersection(interval, |other| {
+                    simultaneous |= other.node_id != interval.node_id;
+                });
+
+                simultaneous
             })
-        }) else {
+        });
+
+        if simultaneous.unwrap_or_default() {
+            return Err(Error::custom("simultaneous with another node"));
+        }
+
+        Ok(())
+    }
+}
+
+impl<T, Extractor> IntervalPlugin<T, Extractor>
+where
+    T: 'static + Identify,
+    T::Id: Clone + PartialEq,
+    Extractor: 'static + Extract<T>,
+    Extractor::Target: Interval + PartialEq,
+{
+    fn on_delete(
+        _: Ctx<T>,
+        target: Target<T>,
+        search_tree: Res<SearchTree<T::Id, Extractor::Target>>,
+        extractor: Res<Extractor>,
+    ) -> Result<()> {
+        let Some(intervals) =
+            (target, extractor).with(|(target, extractor)| node_intervals(target, extractor))
+        else {
             return Ok(());
         };
 
@@ -102,15 +201,9 @@ where
         search_tree: Res<SearchTree<T::Id, Extractor::Target>>,
         extractor: Res<Extractor>,
     ) -> Result<()> {
-        let Some(intervals) = (target, extractor).with(|(target, extractor)| {
-            extractor.all(target).into_iter().map({
-                let node_id = target.id().clone();
-                move |interval| NodeInterval {
-                    node_id: node_id.clone(),
-                    interval,
-                }
-            })
-        }) else {
+        let Some(intervals) =
+            (target, extractor).with(|(target, extractor)| node_intervals(target, extractor))
+        else {
             return Ok(());
         };
 
@@ -135,10 +228,219 @@ where
     where
         T: Identify,
     {
-        schema
+        let search_tree = self
+            .nodes
+            .iter()
+            .flat_map(|node| node_intervals(node, &self.extractor))
+            .fold(SearchTree::default(), SearchTree::with_interval);
+
+        let schema = schema
             .with_resource(self.extractor)
-            .with_resource(SearchTree::<T, Extractor::Target>::default())
+            .with_resource(search_tree)
             .with_trigger(AfterSave, Self::on_save)
-            .with_trigger(AfterDelete, Self::on_delete)
+            .with_trigger(AfterDelete, Self::on_delete);
+
+        if self.simultaneous {
+            return schema;
+        }
+
+        schema.with_trigger(BeforeSave, Self::before_save)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alvidir::{
+        graph::Graph,
+        prelude::*,
+        property::Extract,
+        schema::ops::{delete::Delete, save::Save},
+    };
+
+    #[cfg(feature = "calendar")]
+    use crate::{
+        calendar::YearRange,
+        circa::{Circa, Overlap},
+    };
+    use crate::{
+        fixtures::{interval_mock, IntervalMock},
+        IntervalPlugin,
+    };
+
+    use super::{NodeInterval, SearchTree};
+
+    #[derive(Clone)]
+    struct Node {
+        id: usize,
+        interval: IntervalMock<usize>,
+    }
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    struct IntervalExtractor;
+
+    impl Extract<Node> for IntervalExtractor {
+        type Target = IntervalMock<usize>;
+
+        fn all(&self, node: &Node) -> Vec<Self::Target> {
+            vec![node.interval.clone()]
+        }
+    }
+
+    #[test]
+    fn search_tree_must_follow_the_graph() {
+        let schema = Schema::from(Graph::default()).install(IntervalPlugin::new(IntervalExtractor));
+
+        let node = Node {
+            id: 0,
+            interval: interval_mock!(1, 2),
+        };
+
+        Save::new(node.clone())
+            .execute(schema.transaction())
+            .unwrap();
+
+        let indexed = NodeInterval {
+            node_id: node.id,
+            interval: node.interval.clone(),
+        };
+
+        let search_tree = Res::<SearchTree<usize, IntervalMock<usize>>>::from(schema.resources());
+        assert_eq!(
+            search_tree.with(|tree| tree.intersects(&indexed)),
+            Some(true)
+        );
+
+        Delete::new(node.id).execute(schema.transaction()).unwrap();
+        assert_eq!(
+            search_tree.with(|tree| tree.intersects(&indexed)),
+            Some(false)
+        );
+    }
+
+    #[cfg(feature = "calendar")]
+    #[derive(Debug, Clone)]
+    struct DatedNode {
+        id: usize,
+        period: Circa<YearRange>,
+    }
+
+    #[cfg(feature = "calendar")]
+    impl Identify for DatedNode {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    #[cfg(feature = "calendar")]
+    struct PeriodExtractor;
+
+    #[cfg(feature = "calendar")]
+    impl Extract<DatedNode> for PeriodExtractor {
+        type Target = Circa<YearRange>;
+
+        fn all(&self, node: &DatedNode) -> Vec<Self::Target> {
+            vec![node.period]
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "calendar")]
+    fn simultaneous_nodes() {
+        struct Test<'a> {
+            name: &'a str,
+            simultaneous: bool,
+            overlap: Overlap,
+            node: DatedNode,
+            saved: bool,
+        }
+
+        let existing = DatedNode {
+            id: 0,
+            period: "circa 1800..1805 ± 2y".parse().unwrap(),
+        };
+
+        vec![
+            Test {
+                name: "disjoint periods",
+                simultaneous: false,
+                overlap: Overlap::Possible,
+                node: DatedNode {
+                    id: 1,
+                    period: "1810".parse().unwrap(),
+                },
+                saved: true,
+            },
+            Test {
+                name: "possibly overlapping periods",
+                simultaneous: false,
+                overlap: Overlap::Possible,
+                node: DatedNode {
+                    id: 1,
+                    period: "1807".parse().unwrap(),
+                },
+                saved: false,
+            },
+            Test {
+                name: "possibly overlapping periods when only exact overlaps count",
+                simultaneous: false,
+                overlap: Overlap::Exact,
+                node: DatedNode {
+                    id: 1,
+                    period: "1807".parse().unwrap(),
+                },
+                saved: true,
+            },
+            Test {
+                name: "exactly overlapping periods when only exact overlaps count",
+                simultaneous: false,
+                overlap: Overlap::Exact,
+                node: DatedNode {
+                    id: 1,
+                    period: "1805".parse().unwrap(),
+                },
+                saved: false,
+            },
+            Test {
+                name: "overlapping periods when simultaneity is allowed",
+                simultaneous: true,
+                overlap: Overlap::Possible,
+                node: DatedNode {
+                    id: 1,
+                    period: "1805".parse().unwrap(),
+                },
+                saved: true,
+            },
+            Test {
+                name: "same node with an overlapping period",
+                simultaneous: false,
+                overlap: Overlap::Possible,
+                node: DatedNode {
+                    id: 0,
+                    period: "1805".parse().unwrap(),
+                },
+                saved: true,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let schema = Schema::from(Graph::from_iter([existing.clone()])).install(
+                IntervalPlugin::new(PeriodExtractor)
+                    .with_nodes([existing.clone()])
+                    .with_simultaneity(test.simultaneous)
+                    .with_overlap(test.overlap),
+            );
+
+            let result = Save::new(test.node).execute(schema.transaction());
+            assert_eq!(result.is_ok(), test.saved, "{}", test.name);
+        });
     }
 }
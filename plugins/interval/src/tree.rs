@@ -62,14 +62,32 @@ where
             .unwrap_or_default()
     }
 
+    /// Returns the amount of intervals in the tree.
+    pub fn len(&self) -> usize {
+        self.root
+            .as_ref()
+            .map(|root| root.count())
+            .unwrap_or_default()
+    }
+
+    /// Returns true if, and only if, there are no intervals in the tree.
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns the lowest and highest bounds covered by the intervals in the tree, if any.
+    pub fn span(&self) -> Option<(Intv::Bound, Intv::Bound)> {
+        self.root.as_ref().map(|root| root.span())
+    }
+
     /// Calls the given closure for each interval in the tree overlapping the given one.
     pub fn for_each_intersection<F>(&self, interval: &Intv, f: F)
     where
         F: FnMut(&Intv),
     {
-        self.root
-            .as_ref()
-            .map(|root| root.for_each_intersection(interval, f));
+        if let Some(root) = &self.root {
+            root.for_each_intersection(interval, f);
+        }
     }
 }
 
@@ -200,4 +218,44 @@ mod tests {
             });
         })
     }
+
+    #[test]
+    fn span_of_tree() {
+        struct Test<'a> {
+            name: &'a str,
+            tree: IntervalSearchTree<IntervalMock<usize>>,
+            len: usize,
+            span: Option<(usize, usize)>,
+        }
+
+        vec![
+            Test {
+                name: "empty tree",
+                tree: IntervalSearchTree::default(),
+                len: 0,
+                span: None,
+            },
+            Test {
+                name: "single interval",
+                tree: IntervalSearchTree::default().with_interval(interval_mock!(2, 4)),
+                len: 1,
+                span: Some((2, 4)),
+            },
+            Test {
+                name: "complex tree",
+                tree: IntervalSearchTree::default()
+                    .with_interval(interval_mock!(5, 6))
+                    .with_interval(interval_mock!(1, 4))
+                    .with_interval(interval_mock!(2, 10))
+                    .with_interval(interval_mock!(7, 9)),
+                len: 4,
+                span: Some((1, 10)),
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            assert_eq!(test.tree.len(), test.len, "{}", test.name);
+            assert_eq!(test.tree.span(), test.span, "{}", test.name);
+        });
+    }
 }
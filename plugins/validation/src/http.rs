@@ -0,0 +1,144 @@
+//! A [`Validator`] implementation for external HTTP services.
+
+use std::{collections::HashMap, fmt::Display, sync::Mutex, time::Duration};
+
+use alvidir::property::Extract;
+use ureq::Agent;
+
+use crate::{Validation, Validator};
+
+/// A [`Validator`] that queries an external HTTP service for the keys extracted from each node.
+///
+/// Given a base url, each key is validated by a `GET <url>/<key>` request, with the key
+/// percent-encoded as a single path segment, which must succeed
+/// for the key to be valid. A not found response means the key is unknown, and so the node is
+/// invalid. Any other failure makes the verdict unavailable.
+///
+/// Verdicts are cached by key, except for the unavailable ones.
+pub struct HttpValidator<Extractor> {
+    url: String,
+    agent: Agent,
+    extractor: Extractor,
+    cache: Mutex<HashMap<String, Validation>>,
+}
+
+impl<Extractor> HttpValidator<Extractor> {
+    pub fn new(url: impl Into<String>, timeout: Duration, extractor: Extractor) -> Self {
+        let config = Agent::config_builder()
+            .timeout_global(Some(timeout))
+            .build();
+
+        Self {
+            url: url.into().trim_end_matches('/').to_string(),
+            agent: config.into(),
+            extractor,
+            cache: Default::default(),
+        }
+    }
+
+    /// Returns the url the given key is validated by.
+    fn key_url(&self, key: &str) -> String {
+        let mut url = format!("{}/", self.url);
+        for byte in key.bytes() {
+            match byte {
+                // Unreserved characters, as defined by RFC 3986, are kept as is.
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    url.push(byte as char)
+                }
+                byte => url.push_str(&format!("%{byte:02X}")),
+            }
+        }
+
+        url
+    }
+
+    /// Returns the verdict about the given key.
+    fn validate_key(&self, key: String) -> Validation {
+        if let Some(validation) = self
+            .cache
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&key).cloned())
+        {
+            return validation;
+        }
+
+        let validation = match self.agent.get(self.key_url(&key)).call() {
+            Ok(_) => Validation::Valid,
+            Err(ureq::Error::StatusCode(404)) => {
+                Validation::Invalid(format!("{key} is not registered"))
+            }
+            Err(err) => return Validation::Unavailable(err.to_string()),
+        };
+
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(key, validation.clone());
+        }
+
+        validation
+    }
+}
+
+impl<T, Extractor> Validator<T> for HttpValidator<Extractor>
+where
+    Extractor: Extract<T>,
+    Extractor::Target: Display,
+{
+    fn validate(&self, node: &T) -> Validation {
+        self.extractor
+            .all(node)
+            .into_iter()
+            .map(|key| self.validate_key(key.to_string()))
+            .find(|validation| validation != &Validation::Valid)
+            .unwrap_or(Validation::Valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::HttpValidator;
+
+    #[test]
+    fn key_url() {
+        struct Test {
+            name: &'static str,
+            key: &'static str,
+            url: &'static str,
+        }
+
+        vec![
+            Test {
+                name: "unreserved characters",
+                key: "Villain-1.b_c~d",
+                url: "http://registry/keys/Villain-1.b_c~d",
+            },
+            Test {
+                name: "path separator",
+                key: "sub/b",
+                url: "http://registry/keys/sub%2Fb",
+            },
+            Test {
+                name: "query and fragment delimiters",
+                key: "a?b#c",
+                url: "http://registry/keys/a%3Fb%23c",
+            },
+            Test {
+                name: "whitespaces",
+                key: "the villain",
+                url: "http://registry/keys/the%20villain",
+            },
+            Test {
+                name: "non ascii characters",
+                key: "ñ",
+                url: "http://registry/keys/%C3%B1",
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let validator = HttpValidator::new("http://registry/keys/", Duration::ZERO, ());
+            assert_eq!(validator.key_url(test.key), test.url, "{}", test.name);
+        });
+    }
+}
@@ -0,0 +1,26 @@
+//! Validation of nodes against an external source of truth before saving them.
+
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "http")]
+pub use http::HttpValidator;
+
+mod plugin;
+pub use plugin::ValidationPlugin;
+
+/// The verdict of a [`Validator`] about a node.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Validation {
+    /// The node is accepted.
+    Valid,
+    /// The node is rejected for the given reason.
+    Invalid(String),
+    /// The validator could not reach a verdict for the given reason.
+    Unavailable(String),
+}
+
+/// A source of truth deciding whether nodes of type T are acceptable.
+pub trait Validator<T> {
+    /// Returns the verdict about the given node.
+    fn validate(&self, node: &T) -> Validation;
+}
@@ -0,0 +1,175 @@
+//! The plugin implementation for [`Validator`].
+
+use std::marker::PhantomData;
+
+use alvidir::prelude::*;
+
+use crate::{Validation, Validator};
+
+/// Whether nodes can be saved when the validator is unavailable.
+struct Bypass(bool);
+
+/// Implements the [`Plugin`] trait for an arbitrary validator of nodes of type T, rejecting the
+/// save of any node the validator considers invalid.
+pub struct ValidationPlugin<T, V> {
+    validator: V,
+    bypass: bool,
+    node: PhantomData<T>,
+}
+
+impl<T, V> ValidationPlugin<T, V> {
+    pub fn new(validator: V) -> Self {
+        Self {
+            validator,
+            bypass: false,
+            node: PhantomData,
+        }
+    }
+
+    /// Allows saving nodes when the validator is unavailable, e.g. when working offline.
+    pub fn with_bypass(mut self, bypass: bool) -> Self {
+        self.bypass = bypass;
+        self
+    }
+}
+
+impl<T, V> ValidationPlugin<T, V>
+where
+    T: 'static + Identify,
+    V: 'static + Validator<T>,
+{
+    fn before_save(
+        _: Ctx<T>,
+        target: Target<T>,
+        validator: Res<V>,
+        bypass: Res<Bypass>,
+    ) -> Result<()> {
+        let Some((validation, bypass)) = (target, validator, bypass)
+            .with(|(target, validator, bypass)| (validator.validate(target), bypass.0))
+        else {
+            return Ok(());
+        };
+
+        match validation {
+            Validation::Valid => Ok(()),
+            Validation::Invalid(reason) => Err(Error::custom(reason)),
+            Validation::Unavailable(reason) if bypass => {
+                tracing::warn!(reason, "validation bypassed");
+                Ok(())
+            }
+            Validation::Unavailable(reason) => {
+                Err(Error::custom(format!("validation unavailable: {reason}")))
+            }
+        }
+    }
+}
+
+impl<T, V> Plugin<T> for ValidationPlugin<T, V>
+where
+    T: 'static + Identify,
+    V: 'static + Validator<T>,
+{
+    fn install(self, schema: Schema<T>) -> Schema<T>
+    where
+        T: Identify,
+    {
+        schema
+            .with_resource(self.validator)
+            .with_resource(Bypass(self.bypass))
+            .with_trigger(BeforeSave, Self::before_save)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alvidir::{
+        graph::{Graph, Source},
+        prelude::*,
+        schema::ops::save::Save,
+    };
+
+    use crate::{Validation, ValidationPlugin, Validator};
+
+    #[derive(Debug, Clone)]
+    struct Node {
+        id: usize,
+    }
+
+    impl Identify for Node {
+        type Id = usize;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+    }
+
+    /// Accepts even ids, rejects odd ones and is unavailable for zero.
+    struct EvenValidator;
+
+    impl Validator<Node> for EvenValidator {
+        fn validate(&self, node: &Node) -> Validation {
+            match node.id {
+                0 => Validation::Unavailable("offline".into()),
+                id if id % 2 == 0 => Validation::Valid,
+                _ => Validation::Invalid("odd".into()),
+            }
+        }
+    }
+
+    #[test]
+    fn save_must_be_validated() {
+        struct Test<'a> {
+            name: &'a str,
+            id: usize,
+            bypass: bool,
+            saved: bool,
+        }
+
+        vec![
+            Test {
+                name: "valid node",
+                id: 2,
+                bypass: false,
+                saved: true,
+            },
+            Test {
+                name: "invalid node",
+                id: 1,
+                bypass: false,
+                saved: false,
+            },
+            Test {
+                name: "invalid node with bypass",
+                id: 1,
+                bypass: true,
+                saved: false,
+            },
+            Test {
+                name: "unavailable validator",
+                id: 0,
+                bypass: false,
+                saved: false,
+            },
+            Test {
+                name: "unavailable validator with bypass",
+                id: 0,
+                bypass: true,
+                saved: true,
+            },
+        ]
+        .into_iter()
+        .for_each(|test| {
+            let schema = Schema::from(Graph::default())
+                .install(ValidationPlugin::new(EvenValidator).with_bypass(test.bypass));
+
+            let result = Save::new(Node { id: test.id }).execute(schema.transaction());
+            assert_eq!(result.is_ok(), test.saved, "{}", test.name);
+            assert_eq!(
+                schema.read().contains(&test.id),
+                test.saved,
+                "{}",
+                test.name
+            );
+        });
+    }
+}